@@ -0,0 +1,130 @@
+//! Typed values of custom fields on `Todo`s.
+//!
+//! This module contains the [`FieldValue`] enum holding a value of a custom
+//! field a `Todo` can carry under an arbitrary key, such as a ticket number or
+//! a sprint id. Values are parsed from text by inferring their type, see
+//! [`FieldValue::from_str`].
+//!
+//! # Examples
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use doru::field::FieldValue;
+//!
+//! assert_eq!("42".parse(), Ok(FieldValue::Number(42.0)));
+//! assert_eq!("true".parse(), Ok(FieldValue::Bool(true)));
+//! assert_eq!(
+//!     "2024-12-24".parse(),
+//!     Ok(FieldValue::Date(
+//!         NaiveDate::from_ymd_opt(2024, 12, 24).unwrap()
+//!     ))
+//! );
+//! assert_eq!(
+//!     "ACME Corp".parse(),
+//!     Ok(FieldValue::String(String::from("ACME Corp")))
+//! );
+//! ```
+
+use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Value of a custom field of a Todo item.
+///
+/// Values are stored together with their type, so that e.g. a string that
+/// looks like a number stays a string.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldValue {
+    /// Free-form text.
+    String(String),
+
+    /// A number.
+    Number(f64),
+
+    /// A yes/ no flag.
+    Bool(bool),
+
+    /// A calendar date.
+    Date(NaiveDate),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::String(text) => write!(f, "{text}"),
+            FieldValue::Number(number) => write!(f, "{number}"),
+            FieldValue::Bool(flag) => write!(f, "{flag}"),
+            FieldValue::Date(date) => write!(f, "{date}"),
+        }
+    }
+}
+
+impl FromStr for FieldValue {
+    type Err = Infallible;
+
+    /// Parses a [`FieldValue`], inferring its type.
+    ///
+    /// `true` and `false` are [`Bool`](FieldValue::Bool)s, dates in the
+    /// `YYYY-MM-DD` format are [`Date`](FieldValue::Date)s and anything else
+    /// parseable as a finite number is a [`Number`](FieldValue::Number). Any
+    /// other text, or text enclosed in double quotes, is a
+    /// [`String`](FieldValue::String).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::field::FieldValue;
+    ///
+    /// assert_eq!("\"42\"".parse(), Ok(FieldValue::String(String::from("42"))));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let quoted = s.strip_prefix('"').and_then(|rest| rest.strip_suffix('"'));
+
+        if let Some(text) = quoted {
+            return Ok(FieldValue::String(String::from(text)));
+        }
+
+        let value = match (s, s.parse(), s.parse::<f64>()) {
+            ("true", ..) => FieldValue::Bool(true),
+            ("false", ..) => FieldValue::Bool(false),
+            (_, Ok(date), _) => FieldValue::Date(date),
+            (_, _, Ok(number)) if number.is_finite() => FieldValue::Number(number),
+            _ => FieldValue::String(String::from(s)),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_infers_types() {
+        assert_eq!("-1.5".parse(), Ok(FieldValue::Number(-1.5)));
+        assert_eq!("false".parse(), Ok(FieldValue::Bool(false)));
+        assert_eq!("True".parse(), Ok(FieldValue::String(String::from("True"))));
+        assert_eq!("inf".parse(), Ok(FieldValue::String(String::from("inf"))));
+        assert_eq!("\"\"".parse(), Ok(FieldValue::String(String::new())));
+    }
+
+    #[test]
+    fn values_keep_their_type_through_json() {
+        let values = vec![
+            FieldValue::String(String::from("2024-12-24")),
+            FieldValue::Number(3.0),
+            FieldValue::Bool(true),
+            FieldValue::Date(NaiveDate::from_ymd_opt(2024, 12, 24).unwrap()),
+        ];
+
+        let json = serde_json::to_string(&values).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Vec<FieldValue>>(&json).unwrap(),
+            values
+        );
+    }
+}
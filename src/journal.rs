@@ -0,0 +1,607 @@
+//! Journal of reversible changes made by a `TodoManager`.
+//!
+//! This module contains the [`Journal`] type recording [`Change`]s made to
+//! `Todo`s by the `TodoManager` operations, so that the changes can be undone
+//! and redone. Each [`Change`] is described by an [`Operation`].
+//!
+//! A [`Change`] keeps only the values it changed, as they were before and
+//! after it, such as the old and the new content of an edited `Todo`. Undoing
+//! it reverts just those values, so the other modifications of the same
+//! `Todo`s are kept. Comments and time entries are history of a `Todo`, which
+//! is never undone.
+//!
+//! If the changed values differ from the recorded ones, e.g. because the
+//! `Todo` was moved to another project, the [`Change`] can't be undone nor
+//! redone.
+//!
+//! # Examples
+//!
+//! ```
+//! use doru::journal::Operation;
+//! use doru::todo_manager::TodoManager;
+//!
+//! let mut manager = TodoManager::default();
+//! let id = manager.add_todo("Learn Rust");
+//! manager.delete_todo(id).unwrap();
+//!
+//! assert_eq!(manager.undo(), Ok(Operation::Delete(id)));
+//! assert_eq!(manager.todo_by_id(id).unwrap().content, "Learn Rust");
+//!
+//! assert_eq!(manager.redo(), Ok(Operation::Delete(id)));
+//! assert!(manager.todo_by_id(id).is_none());
+//! ```
+
+use std::{collections::BTreeSet, fmt::Display};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::field::FieldValue;
+use crate::recurrence::Recurrence;
+use crate::reference::Reference;
+use crate::todo::{ChecklistItem, Priority, Todo, TodoStatus};
+
+/// Maximum number of [`Change`]s a [`Journal`] keeps for undoing. Older ones
+/// are forgotten.
+pub const JOURNAL_LIMIT: usize = 100;
+
+/// An operation on a Todo item that can be undone and redone.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Adding a Todo item with the given id.
+    Add(usize),
+
+    /// Editing content of a Todo item with the given id.
+    Edit(usize),
+
+    /// Editing notes of a Todo item with the given id.
+    Notes(usize),
+
+    /// Changing status of a Todo item with the given id.
+    Status(usize),
+
+    /// Changing priority of a Todo item with the given id.
+    Priority(usize),
+
+    /// Changing due date of a Todo item with the given id.
+    Due(usize),
+
+    /// Changing scheduled date of a Todo item with the given id.
+    Schedule(usize),
+
+    /// Hiding a Todo item with the given id until a date, or showing it again.
+    Snooze(usize),
+
+    /// Changing recurrence of a Todo item with the given id.
+    Repeat(usize),
+
+    /// Moving a Todo item with the given id under another parent.
+    Move(usize),
+
+    /// Changing dependencies of a Todo item with the given id.
+    Depend(usize),
+
+    /// Changing tags of a Todo item with the given id.
+    Tag(usize),
+
+    /// Changing assignees of a Todo item with the given id.
+    Assign(usize),
+
+    /// Changing references of a Todo item with the given id.
+    Link(usize),
+
+    /// Changing custom fields of a Todo item with the given id.
+    Field(usize),
+
+    /// Changing checklist of a Todo item with the given id.
+    Checklist(usize),
+
+    /// Deleting a Todo item with the given id, possibly with its subtasks.
+    Delete(usize),
+
+    /// Restoring a deleted Todo item with the given id from the trash.
+    Restore(usize),
+
+    /// Archiving a done Todo item with the given id with its subtasks.
+    Archive(usize),
+
+    /// Archiving all done Todo items.
+    ArchiveDone,
+
+    /// Removing all deleted Todo items from the trash.
+    EmptyTrash,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Add(id) => write!(f, "add Todo {id}"),
+            Operation::Edit(id) => write!(f, "edit Todo {id}"),
+            Operation::Notes(id) => write!(f, "edit notes of Todo {id}"),
+            Operation::Status(id) => write!(f, "change status of Todo {id}"),
+            Operation::Priority(id) => write!(f, "change priority of Todo {id}"),
+            Operation::Due(id) => write!(f, "change due date of Todo {id}"),
+            Operation::Schedule(id) => write!(f, "change scheduled date of Todo {id}"),
+            Operation::Snooze(id) => write!(f, "snooze Todo {id}"),
+            Operation::Repeat(id) => write!(f, "change recurrence of Todo {id}"),
+            Operation::Move(id) => write!(f, "move Todo {id}"),
+            Operation::Depend(id) => write!(f, "change dependencies of Todo {id}"),
+            Operation::Tag(id) => write!(f, "change tags of Todo {id}"),
+            Operation::Assign(id) => write!(f, "change assignees of Todo {id}"),
+            Operation::Link(id) => write!(f, "change references of Todo {id}"),
+            Operation::Field(id) => write!(f, "change fields of Todo {id}"),
+            Operation::Checklist(id) => write!(f, "change checklist of Todo {id}"),
+            Operation::Delete(id) => write!(f, "delete Todo {id}"),
+            Operation::Restore(id) => write!(f, "restore Todo {id}"),
+            Operation::Archive(id) => write!(f, "archive Todo {id}"),
+            Operation::ArchiveDone => write!(f, "archive done Todos"),
+            Operation::EmptyTrash => write!(f, "empty trash"),
+        }
+    }
+}
+
+/// Lists of Todo items kept by a `TodoManager`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum List {
+    Todos,
+    Archive,
+    Trash,
+}
+
+/// A value of a Todo item that can be changed by an [`Operation`]. Values of
+/// collections are kept per entry, e.g. whether the Todo has a single tag.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Value {
+    Content(String),
+    Notes(String),
+    Status(TodoStatus, Option<DateTime<Utc>>),
+    Priority(Priority),
+    Due(Option<NaiveDate>),
+    Scheduled(Option<NaiveDate>),
+    HiddenUntil(Option<NaiveDate>),
+    Recurrence(Option<Recurrence>),
+    Parent(Option<usize>),
+    Dependency(usize, bool),
+    Tag(String, bool),
+    Assignee(String, bool),
+    Field(String, Option<FieldValue>),
+    References(Vec<Reference>),
+    Checklist(Vec<ChecklistItem>),
+}
+
+impl Value {
+    /// Returns the value of the same kind the provided Todo item has now.
+    pub(crate) fn of(&self, todo: &Todo) -> Value {
+        match self {
+            Value::Content(_) => Value::Content(todo.content.clone()),
+            Value::Notes(_) => Value::Notes(todo.notes.clone()),
+            Value::Status(..) => Value::Status(todo.status.clone(), todo.completed),
+            Value::Priority(_) => Value::Priority(todo.priority),
+            Value::Due(_) => Value::Due(todo.due),
+            Value::Scheduled(_) => Value::Scheduled(todo.scheduled),
+            Value::HiddenUntil(_) => Value::HiddenUntil(todo.hidden_until),
+            Value::Recurrence(_) => Value::Recurrence(todo.recurrence),
+            Value::Parent(_) => Value::Parent(todo.parent),
+            Value::Dependency(id, _) => Value::Dependency(*id, todo.depends_on.contains(id)),
+            Value::Tag(tag, _) => Value::Tag(tag.clone(), todo.tags.contains(tag)),
+            Value::Assignee(user, _) => {
+                Value::Assignee(user.clone(), todo.assignees.contains(user))
+            }
+            Value::Field(key, _) => Value::Field(key.clone(), todo.fields.get(key).cloned()),
+            Value::References(_) => Value::References(todo.references.clone()),
+            Value::Checklist(_) => Value::Checklist(todo.checklist.clone()),
+        }
+    }
+
+    /// Sets this value to the provided Todo item.
+    pub(crate) fn write(&self, todo: &mut Todo) {
+        match self.clone() {
+            Value::Content(content) => todo.content = content,
+            Value::Notes(notes) => todo.notes = notes,
+            Value::Status(status, completed) => {
+                todo.status = status;
+                todo.completed = completed;
+            }
+            Value::Priority(priority) => todo.priority = priority,
+            Value::Due(due) => todo.due = due,
+            Value::Scheduled(scheduled) => todo.scheduled = scheduled,
+            Value::HiddenUntil(until) => todo.hidden_until = until,
+            Value::Recurrence(recurrence) => todo.recurrence = recurrence,
+            Value::Parent(parent) => todo.parent = parent,
+            Value::Dependency(id, true) => {
+                todo.depends_on.insert(id);
+            }
+            Value::Dependency(id, false) => {
+                todo.depends_on.remove(&id);
+            }
+            Value::Tag(tag, true) => {
+                todo.tags.insert(tag);
+            }
+            Value::Tag(tag, false) => {
+                todo.tags.remove(&tag);
+            }
+            Value::Assignee(user, true) => {
+                todo.assignees.insert(user);
+            }
+            Value::Assignee(user, false) => {
+                todo.assignees.remove(&user);
+            }
+            Value::Field(key, Some(value)) => {
+                todo.fields.insert(key, value);
+            }
+            Value::Field(key, None) => {
+                todo.fields.remove(&key);
+            }
+            Value::References(references) => todo.references = references,
+            Value::Checklist(checklist) => todo.checklist = checklist,
+        }
+    }
+}
+
+/// A single step of a [`Change`].
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Step {
+    /// A Todo item was inserted into a list at the index.
+    Insert {
+        list: List,
+        index: usize,
+        todo: Todo,
+    },
+
+    /// A Todo item was removed from a list at the index.
+    Remove {
+        list: List,
+        index: usize,
+        todo: Todo,
+    },
+
+    /// A Todo item with the id was moved from a list at the index to another
+    /// list at the index.
+    Move {
+        id: usize,
+        from: (List, usize),
+        to: (List, usize),
+    },
+
+    /// A value of a Todo item with the id changed.
+    Set { id: usize, old: Value, new: Value },
+}
+
+impl Step {
+    /// Returns the `Step`s setting the values of the Todo item that differ
+    /// between its provided versions, from the earlier to the later one.
+    pub(crate) fn between(before: &Todo, after: &Todo) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let mut set = |old, new| {
+            steps.push(Step::Set {
+                id: after.id(),
+                old,
+                new,
+            })
+        };
+
+        if before.content != after.content {
+            set(
+                Value::Content(before.content.clone()),
+                Value::Content(after.content.clone()),
+            );
+        }
+
+        if before.notes != after.notes {
+            set(
+                Value::Notes(before.notes.clone()),
+                Value::Notes(after.notes.clone()),
+            );
+        }
+
+        if (&before.status, before.completed) != (&after.status, after.completed) {
+            set(
+                Value::Status(before.status.clone(), before.completed),
+                Value::Status(after.status.clone(), after.completed),
+            );
+        }
+
+        if before.priority != after.priority {
+            set(
+                Value::Priority(before.priority),
+                Value::Priority(after.priority),
+            );
+        }
+
+        if before.due != after.due {
+            set(Value::Due(before.due), Value::Due(after.due));
+        }
+
+        if before.scheduled != after.scheduled {
+            set(
+                Value::Scheduled(before.scheduled),
+                Value::Scheduled(after.scheduled),
+            );
+        }
+
+        if before.hidden_until != after.hidden_until {
+            set(
+                Value::HiddenUntil(before.hidden_until),
+                Value::HiddenUntil(after.hidden_until),
+            );
+        }
+
+        if before.recurrence != after.recurrence {
+            set(
+                Value::Recurrence(before.recurrence),
+                Value::Recurrence(after.recurrence),
+            );
+        }
+
+        if before.parent != after.parent {
+            set(Value::Parent(before.parent), Value::Parent(after.parent));
+        }
+
+        for id in before.depends_on.symmetric_difference(&after.depends_on) {
+            let added = after.depends_on.contains(id);
+            set(
+                Value::Dependency(*id, !added),
+                Value::Dependency(*id, added),
+            );
+        }
+
+        for tag in before.tags.symmetric_difference(&after.tags) {
+            let added = after.tags.contains(tag);
+            set(
+                Value::Tag(tag.clone(), !added),
+                Value::Tag(tag.clone(), added),
+            );
+        }
+
+        for user in before.assignees.symmetric_difference(&after.assignees) {
+            let added = after.assignees.contains(user);
+            set(
+                Value::Assignee(user.clone(), !added),
+                Value::Assignee(user.clone(), added),
+            );
+        }
+
+        let keys: BTreeSet<&String> = before.fields.keys().chain(after.fields.keys()).collect();
+
+        for key in keys {
+            let (old, new) = (before.fields.get(key), after.fields.get(key));
+
+            if old != new {
+                set(
+                    Value::Field(key.clone(), old.cloned()),
+                    Value::Field(key.clone(), new.cloned()),
+                );
+            }
+        }
+
+        if before.references != after.references {
+            set(
+                Value::References(before.references.clone()),
+                Value::References(after.references.clone()),
+            );
+        }
+
+        if before.checklist != after.checklist {
+            set(
+                Value::Checklist(before.checklist.clone()),
+                Value::Checklist(after.checklist.clone()),
+            );
+        }
+
+        steps
+    }
+
+    /// Returns the `Step` reverting this one.
+    pub(crate) fn inverse(&self) -> Step {
+        match self.clone() {
+            Step::Insert { list, index, todo } => Step::Remove { list, index, todo },
+            Step::Remove { list, index, todo } => Step::Insert { list, index, todo },
+            Step::Move { id, from, to } => Step::Move {
+                id,
+                from: to,
+                to: from,
+            },
+            Step::Set { id, old, new } => Step::Set {
+                id,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+/// A recorded [`Operation`] together with the [`Step`]s it made.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    operation: Operation,
+    steps: Vec<Step>,
+}
+
+impl Change {
+    /// Creates a `Change` made by the provided [`Operation`] in the provided
+    /// [`Step`]s.
+    pub(crate) fn new(operation: Operation, steps: Vec<Step>) -> Self {
+        Self { operation, steps }
+    }
+
+    /// Returns the [`Operation`] that made this `Change`.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Returns the [`Step`]s of this `Change`, in the order they were made.
+    pub(crate) fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+}
+
+/// A `Journal` of [`Change`]s that can be undone, and of undone [`Change`]s
+/// that can be redone.
+///
+/// Recording a new [`Change`] forgets the ones that can be redone.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) undo: Vec<Change>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) redo: Vec<Change>,
+
+    /// [`Change`]s recorded since a group was started, if any.
+    #[serde(skip)]
+    group: Option<Vec<Change>>,
+}
+
+impl Journal {
+    /// Returns `true` if the `Journal` has nothing to undo nor redo.
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty() && self.redo.is_empty()
+    }
+
+    /// Returns the [`Operation`] that would be undone next, if any.
+    pub fn next_undo(&self) -> Option<Operation> {
+        self.undo.last().map(Change::operation)
+    }
+
+    /// Returns the [`Operation`] that would be redone next, if any.
+    pub fn next_redo(&self) -> Option<Operation> {
+        self.redo.last().map(Change::operation)
+    }
+
+    /// Records a new [`Change`], keeping at most [`JOURNAL_LIMIT`] of them.
+    pub(crate) fn record(&mut self, change: Change) {
+        self.redo.clear();
+
+        if let Some(group) = &mut self.group {
+            group.push(change);
+            return;
+        }
+
+        self.undo.push(change);
+
+        if self.undo.len() > JOURNAL_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Starts a group of [`Change`]s recorded as a single one, returning
+    /// `false` if a group was already started.
+    pub(crate) fn start_group(&mut self) -> bool {
+        if self.group.is_some() {
+            return false;
+        }
+
+        self.group = Some(Vec::new());
+        true
+    }
+
+    /// Records the [`Change`]s of the started group as a single one, made by
+    /// the [`Operation`] of the first of them.
+    pub(crate) fn end_group(&mut self) {
+        let mut changes = self.group.take().unwrap_or_default().into_iter();
+
+        if let Some(mut merged) = changes.next() {
+            for change in changes {
+                merged.steps.extend(change.steps);
+            }
+
+            self.record(merged);
+        }
+    }
+
+    /// Forgets the undone [`Change`]s after an unrecorded modification, which
+    /// they might conflict with.
+    pub(crate) fn forget_undone(&mut self) {
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn steps_between_todos_keep_only_changed_values() {
+        let before = Todo::new(1, "Lorem");
+        let mut after = before.clone();
+        after.content = String::from("Ipsum");
+        after.tags.insert(String::from("dolor"));
+        after
+            .fields
+            .insert(String::from("sit"), FieldValue::Bool(true));
+
+        let steps = Step::between(&before, &after);
+
+        assert_eq!(
+            steps,
+            vec![
+                Step::Set {
+                    id: 1,
+                    old: Value::Content(String::from("Lorem")),
+                    new: Value::Content(String::from("Ipsum")),
+                },
+                Step::Set {
+                    id: 1,
+                    old: Value::Tag(String::from("dolor"), false),
+                    new: Value::Tag(String::from("dolor"), true),
+                },
+                Step::Set {
+                    id: 1,
+                    old: Value::Field(String::from("sit"), None),
+                    new: Value::Field(String::from("sit"), Some(FieldValue::Bool(true))),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn value_of_todo_matches_written_value() {
+        let mut todo = Todo::new(1, "Lorem");
+        let value = Value::Assignee(String::from("ipsum"), true);
+
+        assert_eq!(
+            value.of(&todo),
+            Value::Assignee(String::from("ipsum"), false)
+        );
+
+        value.write(&mut todo);
+        assert_eq!(value.of(&todo), value);
+    }
+
+    #[test]
+    fn grouped_changes_are_recorded_as_first_one() {
+        let change = |id| Change::new(Operation::Add(id), Vec::new());
+        let mut journal = Journal::default();
+
+        assert!(journal.start_group());
+        assert!(!journal.start_group());
+
+        journal.record(change(1));
+        journal.record(change(2));
+        assert!(journal.undo.is_empty());
+
+        journal.end_group();
+        assert_eq!(journal.undo, vec![change(1)]);
+    }
+
+    #[test]
+    fn journal_forgets_oldest_changes_and_redo_on_record() {
+        let change = |id| Change::new(Operation::Add(id), Vec::new());
+        let mut journal = Journal::default();
+
+        journal.redo.push(change(0));
+
+        for id in 1..=JOURNAL_LIMIT + 1 {
+            journal.record(change(id));
+        }
+
+        assert!(journal.redo.is_empty());
+        assert_eq!(journal.undo.len(), JOURNAL_LIMIT);
+        assert_eq!(journal.undo[0].operation(), Operation::Add(2));
+        assert_eq!(journal.next_undo(), Some(Operation::Add(JOURNAL_LIMIT + 1)));
+    }
+}
@@ -4,9 +4,14 @@
 //! is a `TodoManager` that manages a vector of `Todo`s - any interaction with
 //! individual `Todo`s is handled by the Manager.
 //!
+//! Changes made by a `TodoManager` are recorded in a `Journal`, so that they
+//! can be undone and redone.
+//!
+//! Several `TodoManager`s can be grouped into named projects of a `Workspace`.
+//!
 //! Additionally, a `TodoStorage` trait defines the contract for loading and
-//! storing `Todo`s from/ to arbitrary text format. An example JSON storage
-//! implementing the trait is provided.
+//! storing `Todo`s, or a whole `Workspace`, from/ to arbitrary text format. An
+//! example JSON storage implementing the trait is provided.
 //!
 //! In some cases, the operations can fail. The `TodoError` enum defines the
 //! possible errors.
@@ -31,18 +36,141 @@
 //! }
 //! ```
 
+pub mod field;
+
+pub mod journal;
+
+pub mod query;
+
+pub mod recurrence;
+
+pub mod reference;
+
+pub mod search;
+
+pub mod sort;
+
 pub mod storage;
 
 pub mod todo;
 
 pub mod todo_manager;
 
+pub mod workflow;
+
+pub mod workspace;
+
 use thiserror::Error;
 
+use crate::journal::Operation;
+use crate::todo::TodoStatus;
+
 /// Possible errors that can occur while managing Todo items.
 #[derive(Error, Debug, PartialEq)]
 pub enum TodoError {
     /// Error indicating that a Todo item with the specified ID was not found.
     #[error("Todo with ID {0} not found!")]
     NotFound(usize),
+
+    /// Error indicating that a project with the specified name was not found.
+    #[error("Project {0} not found!")]
+    ProjectNotFound(String),
+
+    /// Error indicating that a Todo item can't be deleted on its own, because
+    /// it has subtasks.
+    #[error("Todo with ID {0} has subtasks!")]
+    HasSubtasks(usize),
+
+    /// Error indicating that a Todo item can't become a subtask of the other
+    /// Todo item, because the other item is already its subtask.
+    #[error("Todo with ID {0} can't be moved under its own subtask {1}!")]
+    InvalidParent(usize, usize),
+
+    /// Error indicating that a Todo item can't depend on a Todo item that
+    /// doesn't exist.
+    #[error("Dependency with ID {0} not found!")]
+    MissingDependency(usize),
+
+    /// Error indicating that a dependency between two Todo items would create
+    /// a cycle.
+    #[error("Todo with ID {0} can't depend on {1}, because {1} already depends on it!")]
+    DependencyCycle(usize, usize),
+
+    /// Error indicating that a status is not defined by the workflow in use.
+    #[error("Status {0} is not defined!")]
+    UnknownStatus(String),
+
+    /// Error indicating that the transition policy in use doesn't allow the
+    /// status change of a Todo item.
+    #[error("Todo with ID {0} can't change status from {1} to {2}!")]
+    InvalidTransition(usize, TodoStatus, TodoStatus),
+
+    /// Error indicating that a Todo item can't be done, because Todo items it
+    /// depends on are not done yet.
+    #[error("Todo with ID {0} is blocked by unfinished Todos {1:?}!")]
+    Blocked(usize, Vec<usize>),
+
+    /// Error indicating that a Todo item has no checklist entry at the given
+    /// zero-based index. The message numbers entries from one.
+    #[error("Todo with ID {0} has no checklist entry number {number}!", number = .1 + 1)]
+    ChecklistItemNotFound(usize, usize),
+
+    /// Error indicating that a Todo item can't be archived, because it or any
+    /// of its subtasks is not done.
+    #[error("Todo with ID {0} and all its subtasks have to be done to be archived!")]
+    NotDone(usize),
+
+    /// Error indicating that the next occurrence of a repeating Todo item
+    /// would be due out of the supported range of dates.
+    #[error(
+        "Next occurrence of Todo with ID {0} would be due out of the supported range of dates!"
+    )]
+    RecurrenceOutOfRange(usize),
+
+    /// Error indicating that the timer of a Todo item is already running.
+    #[error("Timer of Todo with ID {0} is already running!")]
+    TimerRunning(usize),
+
+    /// Error indicating that no timer is running.
+    #[error("No timer is running!")]
+    NoTimerRunning,
+
+    /// Error indicating that there is no recorded change to undo.
+    #[error("Nothing to undo!")]
+    NothingToUndo,
+
+    /// Error indicating that there is no undone change to redo.
+    #[error("Nothing to redo!")]
+    NothingToRedo,
+
+    /// Error indicating that a recorded change can't be undone, because the
+    /// Todo items it modified have changed since without being recorded.
+    #[error("Can't undo {0}, its Todos have changed since! The change is forgotten.")]
+    UndoConflict(Operation),
+
+    /// Error indicating that an undone change can't be redone, because the
+    /// Todo items it modifies have changed since.
+    #[error("Can't redo {0}, its Todos have changed since! The change is forgotten.")]
+    RedoConflict(Operation),
+
+    /// Error indicating that no Todo item matches a text selector.
+    #[error("No Todo matches {0:?}!")]
+    NoMatch(String),
+
+    /// Error indicating that a text selector matches several Todo items,
+    /// listed by their ids and contents.
+    #[error(
+        "{0:?} matches several Todos, select one by its ID: {candidates}!",
+        candidates = list_candidates(.1)
+    )]
+    AmbiguousSelector(String, Vec<(usize, String)>),
+}
+
+/// Returns the ids and contents of Todo items, separated by commas.
+fn list_candidates(candidates: &[(usize, String)]) -> String {
+    candidates
+        .iter()
+        .map(|(id, content)| format!("{id} ({content})"))
+        .collect::<Vec<String>>()
+        .join(", ")
 }
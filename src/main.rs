@@ -2,14 +2,35 @@
 //! doru library. It defines the command-line interface (CLI) and handles the
 //! execution of commands.
 
-use std::{env, error::Error, fs, path::Path, path::PathBuf};
+use std::{
+    collections::BTreeSet,
+    env,
+    error::Error,
+    fs,
+    io::{self, IsTerminal},
+    ops::Range,
+    path::Path,
+    path::PathBuf,
+    process,
+};
 
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use clap::{Args, Parser, Subcommand};
 use doru::{
+    field::FieldValue,
+    query::Query,
+    recurrence::Recurrence,
+    reference::Reference,
+    search::{SearchHit, SearchQuery},
+    sort::{self, SortKey},
     storage::{self, TodoStorage},
-    todo::TodoStatus,
+    todo::{Priority, Todo, TodoStatus},
     todo_manager::TodoManager,
+    workflow::{TransitionPolicy, Workflow},
+    workspace::{Workspace, DEFAULT_PROJECT},
+    TodoError,
 };
+use serde::Deserialize;
 
 /// CLI structure for the `doru` application.
 #[derive(Parser)]
@@ -22,25 +43,474 @@ struct Cli {
     /// Optional path to the todos file.
     #[arg(short, long, global = true)]
     path: Option<String>,
+
+    /// Project to work with. Adding a TODO item creates the project.
+    #[arg(short = 'P', long, global = true, default_value = DEFAULT_PROJECT)]
+    project: String,
 }
 
 /// Available commands for the `doru` application.
 #[derive(Subcommand)]
 enum Commands {
+    #[command(flatten)]
+    Todo(TodoCommands),
+
+    /// List all projects with the number of TODO items in them.
+    Projects,
+
+    /// Start tracking time spent on an existing TODO item and mark it in
+    /// progress, stopping any other running timer.
+    Start {
+        #[command(flatten)]
+        todo: Selector,
+    },
+
+    /// Stop the running timer.
+    Stop,
+
+    /// Move an existing TODO item, with all its subtasks, to another project.
+    Transfer {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// Name of the project to move the TODO item to.
+        #[arg(value_name = "PROJECT")]
+        target: String,
+    },
+}
+
+/// Commands working with TODO items of a single project.
+#[derive(Subcommand)]
+enum TodoCommands {
     /// Add a new TODO item.
-    Add { content: String },
+    Add {
+        content: String,
+
+        /// Date by which the TODO should be done (YYYY-MM-DD).
+        #[arg(long)]
+        due: Option<NaiveDate>,
+
+        /// Date on which work on the TODO is planned (YYYY-MM-DD).
+        #[arg(long)]
+        scheduled: Option<NaiveDate>,
+
+        /// Importance of the TODO.
+        #[arg(long)]
+        priority: Option<Priority>,
+
+        /// Tag to label the TODO with. Can be repeated.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
 
-    /// Edit the content of an existing TODO item.
-    Edit { id: usize, content: String },
+        /// ID or words of the content of the TODO item the new TODO is a
+        /// subtask of.
+        #[arg(long)]
+        parent: Option<String>,
+
+        /// Repeat the TODO once done (daily, weekly, monthly, monthly:DAY,
+        /// every:N or after:N days).
+        #[arg(long)]
+        repeat: Option<Recurrence>,
+    },
+
+    /// Edit the content or priority of an existing TODO item.
+    Edit {
+        #[command(flatten)]
+        todo: Selector,
+        content: Option<String>,
+
+        /// New importance of the TODO.
+        #[arg(long)]
+        priority: Option<Priority>,
+    },
 
     /// List TODO items, optionally filtered by status.
-    List { status: Option<TodoStatus> },
+    List {
+        #[command(flatten)]
+        filters: ListFilters,
+
+        /// Also list snoozed TODO items.
+        #[arg(long)]
+        all: bool,
+
+        /// Also list archived TODO items.
+        #[arg(long)]
+        archived: bool,
+
+        /// Show subtasks indented under their parent TODO items.
+        #[arg(long)]
+        tree: bool,
+
+        /// Order in which the TODO items are listed, as comma-separated keys
+        /// (id, parent, status, priority, content, due, scheduled, created,
+        /// updated, completed), each prefixed with - for descending order.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "KEYS",
+            allow_hyphen_values = true
+        )]
+        sort: Vec<SortKey>,
+    },
+
+    /// Search TODO items by words in their content and notes, listing the
+    /// most relevant first with the matches highlighted.
+    Search {
+        /// Words to find, ignoring case and diacritics. A word also matches
+        /// longer words starting with it. Quote a phrase, such as
+        /// "deploy to stag", to match its words next to each other.
+        #[arg(required = true)]
+        terms: Vec<String>,
+    },
+
+    /// Show all details of an existing TODO item, including its notes.
+    Show {
+        #[command(flatten)]
+        todo: Selector,
+    },
+
+    /// Add notes to an existing TODO item.
+    Note {
+        #[command(flatten)]
+        todo: Selector,
+        text: String,
+
+        /// Replace the existing notes instead of appending to them.
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Add a comment to the log of an existing TODO item, signed by the
+    /// current user, if set.
+    Comment {
+        #[command(flatten)]
+        todo: Selector,
+        text: String,
+    },
 
     /// Change the status of an existing TODO item.
-    Status { id: usize, status: TodoStatus },
+    Status {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// Built-in (open, in-progress, done) or configured custom status.
+        status: TodoStatus,
+
+        /// Allow a done TODO item to become not done, if configured to
+        /// require reopening.
+        #[arg(long)]
+        reopen: bool,
+    },
+
+    /// Label an existing TODO item with one or more tags.
+    Tag {
+        #[command(flatten)]
+        todo: Selector,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove one or more tags from an existing TODO item.
+    Untag {
+        #[command(flatten)]
+        todo: Selector,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// List all tags with the number of TODO items labeled with them.
+    Tags,
+
+    /// Assign an existing TODO item to one or more users.
+    Assign {
+        #[command(flatten)]
+        todo: Selector,
+        #[arg(required = true)]
+        users: Vec<String>,
+
+        /// Remove the users from the assignees instead of adding them.
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Attach URLs, local files or other TODO items to an existing TODO item.
+    Link {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// URLs, file paths with an optional :LINE, or #ID of TODO items.
+        #[arg(required = true)]
+        references: Vec<Reference>,
+
+        /// Remove the references instead of adding them.
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Print the references of an existing TODO item.
+    Open {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// Open URLs and files with the default application instead.
+        #[arg(long)]
+        launch: bool,
+    },
+
+    /// Set custom fields of an existing TODO item.
+    Set {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// Fields as key=value. Values are numbers, true/ false, dates
+        /// (YYYY-MM-DD) or text; quote a value to keep it as text.
+        #[arg(required = true, value_parser = parse_field)]
+        fields: Vec<(String, FieldValue)>,
+    },
+
+    /// Remove custom fields from an existing TODO item.
+    Unset {
+        #[command(flatten)]
+        todo: Selector,
+        #[arg(required = true)]
+        keys: Vec<String>,
+    },
+
+    /// Hide an existing TODO item from `doru list` until the given date.
+    Snooze {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// Date (YYYY-MM-DD), tomorrow, or a number of days (3d) or weeks (2w)
+        /// from today. Omit to show the TODO again.
+        #[arg(value_parser = parse_when)]
+        when: Option<NaiveDate>,
+    },
+
+    /// Manage the checklist of an existing TODO item.
+    Checklist {
+        #[command(flatten)]
+        todo: Selector,
+
+        #[command(subcommand)]
+        command: ChecklistCommands,
+    },
+
+    /// Set how an existing TODO item repeats once done, or stop repeating it.
+    Repeat {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// Daily, weekly, monthly, monthly:DAY, every:N or after:N days. Omit
+        /// to stop repeating the TODO.
+        recurrence: Option<Recurrence>,
+    },
+
+    /// Make an existing TODO item depend on other TODO items.
+    Depend {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// IDs or words of the content of the TODO items that have to be
+        /// done first.
+        #[arg(required = true)]
+        dependencies: Vec<String>,
+
+        /// Remove the dependencies instead of adding them.
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Move an existing TODO item, with all its subtasks, under another one.
+    Move {
+        #[command(flatten)]
+        todo: Selector,
+
+        /// ID or words of the content of the new parent TODO item. Omit to
+        /// move to the top level.
+        #[arg(long)]
+        parent: Option<String>,
+    },
+
+    /// Delete an existing TODO item, moving it to the trash.
+    Delete {
+        #[command(flatten)]
+        todo: Selector,
 
-    /// Delete an existing TODO item.
-    Delete { id: usize },
+        /// Delete the TODO item together with all its subtasks.
+        #[arg(long)]
+        cascade: bool,
+    },
+
+    /// Archive an existing done TODO item, or all done TODO items.
+    Archive {
+        /// ID or words of the content of the TODO item to archive. Omit to
+        /// archive all done ones.
+        todo: Option<String>,
+    },
+
+    /// List, restore or remove deleted TODO items.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+
+    /// Undo the last change of TODO items.
+    Undo,
+
+    /// Redo the last undone change.
+    Redo,
+}
+
+/// Commands working with the checklist of a TODO item. Checklist entries are
+/// numbered from 1, as shown by `doru show`.
+#[derive(Subcommand)]
+enum ChecklistCommands {
+    /// Append an entry to the checklist.
+    Add { text: String },
+
+    /// Check off an entry of the checklist.
+    Check {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        number: u32,
+    },
+
+    /// Uncheck an entry of the checklist.
+    Uncheck {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        number: u32,
+    },
+
+    /// Remove an entry from the checklist.
+    Remove {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        number: u32,
+    },
+}
+
+/// Commands working with deleted TODO items.
+#[derive(Subcommand)]
+enum TrashCommands {
+    /// List deleted TODO items.
+    List,
+
+    /// Restore a deleted TODO item, together with subtasks deleted with it.
+    Restore {
+        #[command(flatten)]
+        todo: Selector,
+    },
+
+    /// Remove all deleted TODO items from the trash. Can be undone with
+    /// `doru undo`.
+    Empty,
+}
+
+/// A TODO item selected on the command line.
+#[derive(Args)]
+struct Selector {
+    /// ID of the TODO item, or words of its content, such as "deploy stag".
+    todo: String,
+}
+
+impl Selector {
+    /// Get the id of the selected TODO item of the `TodoManager`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no TODO item or several TODO items match the words.
+    fn resolve(&self, todo_manager: &TodoManager) -> Result<usize, TodoError> {
+        select_todo(todo_manager, &self.todo)
+    }
+
+    /// Get the id of the selected deleted TODO item of the `TodoManager`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no deleted TODO item or several deleted TODO items
+    /// match the words.
+    fn resolve_trashed(&self, todo_manager: &TodoManager) -> Result<usize, TodoError> {
+        match self.todo.trim().parse() {
+            Ok(id) => Ok(id),
+            Err(_) => todo_manager.find_trashed_todo(&self.todo).map(Todo::id),
+        }
+    }
+}
+
+/// Filters selecting the TODO items listed by `doru list`.
+#[derive(Args)]
+struct ListFilters {
+    /// Built-in (open, in-progress, done) or configured custom status.
+    status: Option<TodoStatus>,
+
+    /// Only list TODO items matching the query, such as
+    /// 'status:open and (tag:infra or content~"deploy") and id>10'.
+    #[arg(short, long)]
+    query: Option<Query>,
+
+    /// Only list TODO items that are past their due date.
+    #[arg(long)]
+    overdue: bool,
+
+    /// Only list TODO items due before the given date (YYYY-MM-DD).
+    #[arg(long)]
+    due_before: Option<NaiveDate>,
+
+    /// Only list TODO items with the given priority.
+    #[arg(long)]
+    priority: Option<Priority>,
+
+    /// Only list TODO items labeled with the given tag. Can be repeated.
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+
+    /// List TODO items labeled with any, instead of all, of the given tags.
+    #[arg(long, requires = "tags")]
+    any_tag: bool,
+
+    /// Only list TODO items assigned to the current user, set by
+    /// DORU_USER or "user" in the config file.
+    #[arg(long)]
+    mine: bool,
+
+    /// Only list TODO items assigned to the given user.
+    #[arg(long)]
+    assignee: Option<String>,
+
+    /// Only list TODO items with the given custom field value, as
+    /// key=value. Can be repeated.
+    #[arg(long = "field", value_parser = parse_field)]
+    fields: Vec<(String, FieldValue)>,
+
+    /// Only list TODO items created in the last given number of days.
+    #[arg(long, value_name = "DAYS")]
+    created_within: Option<i64>,
+
+    /// Only list TODO items modified in the last given number of days.
+    #[arg(long, value_name = "DAYS")]
+    updated_within: Option<i64>,
+
+    /// Only list TODO items done in the last given number of days.
+    #[arg(long, value_name = "DAYS")]
+    completed_within: Option<i64>,
+
+    /// Only list TODO items that are not done and don't wait for others.
+    #[arg(long)]
+    ready: bool,
+}
+
+/// User configuration of the `doru` application, read from a JSON file.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Config {
+    /// Custom statuses TODO items can have, in addition to the built-in ones.
+    statuses: Workflow,
+
+    /// Restrictions on changing status of TODO items.
+    transitions: TransitionPolicy,
+
+    /// Name of the current user, for `doru list --mine` and signing comments.
+    /// The `DORU_USER` environment variable takes precedence.
+    user: Option<String>,
 }
 
 /// Main entry point for the `doru` application.
@@ -54,41 +524,901 @@ fn main() {
 
     ensure_storage_exists(&path).expect("Failed reaching storage path.");
 
-    let todos = storage::JsonStorage::load(&path).unwrap_or_else(|e| panic!("{e}"));
+    let config = get_config_path()
+        .and_then(|config_path| load_config(&config_path))
+        .unwrap_or_else(|e| panic!("{e}"));
+
+    let user = env::var("DORU_USER").ok().or(config.user);
 
-    let mut todo_manager = TodoManager::new(todos);
+    let mut workspace = storage::JsonStorage::load_workspace(&path)
+        .unwrap_or_else(|e| panic!("{e}"))
+        .with_workflow(config.statuses)
+        .with_transition_policy(config.transitions);
+
+    run(cli.command, &cli.project, user.as_deref(), &mut workspace)
+        .unwrap_or_else(|e| println!("{e}"));
+
+    storage::JsonStorage::save_workspace(&workspace, &path).unwrap_or_else(|e| panic!("{e}"));
+}
 
-    match cli.command {
-        Commands::Add { content } => {
-            todo_manager.add_todo(&content);
+/// Execute a single command against the provided `Workspace`, working with
+/// TODO items of the given project on behalf of the given current user.
+///
+/// # Errors
+///
+/// Returns an error if the command refers to a project or a TODO item that
+/// doesn't exist.
+fn run(
+    command: Commands,
+    project: &str,
+    user: Option<&str>,
+    workspace: &mut Workspace,
+) -> Result<(), TodoError> {
+    match command {
+        Commands::Todo(command @ TodoCommands::Add { .. }) => {
+            let todo_manager = workspace.project_or_create(project);
+            todo_manager.grouped(|todo_manager| execute(command, todo_manager, user))?;
         }
 
-        Commands::Edit { id, content } => todo_manager
-            .edit_todo_content(id, &content)
-            .unwrap_or_else(|e| println!("{e}")),
+        Commands::Todo(command) => {
+            let todo_manager = workspace
+                .project_mut(project)
+                .ok_or_else(|| TodoError::ProjectNotFound(String::from(project)))?;
 
-        Commands::List { status } => {
-            let todos = if let Some(value) = status {
-                todo_manager.todos_by_status(value)
-            } else {
+            todo_manager.grouped(|todo_manager| execute(command, todo_manager, user))?;
+        }
+
+        Commands::Projects => {
+            for name in workspace.project_names() {
+                let count = workspace
+                    .project(name)
+                    .map_or(0, |todo_manager| todo_manager.all_todos().len());
+
+                println!("{name:<20} {count}");
+            }
+        }
+
+        Commands::Start { todo } => {
+            let running = running_timer_project(workspace);
+
+            let todo_manager = workspace
+                .project_mut(project)
+                .ok_or_else(|| TodoError::ProjectNotFound(String::from(project)))?;
+
+            let id = todo.resolve(todo_manager)?;
+            todo_manager.start_todo(id)?;
+
+            if let Some(other) = running.filter(|other| other != project) {
+                if let Some(todo_manager) = workspace.project_mut(&other) {
+                    todo_manager.stop_timer()?;
+                }
+            }
+        }
+
+        Commands::Stop => {
+            let running = running_timer_project(workspace).ok_or(TodoError::NoTimerRunning)?;
+            let todo_manager = workspace
+                .project_mut(&running)
+                .ok_or(TodoError::ProjectNotFound(running))?;
+
+            let id = todo_manager.stop_timer()?;
+            let spent = todo_manager.time_spent(id)?;
+            println!(
+                "Stopped timer of TODO {id}, {} spent in total.",
+                format_duration(spent)
+            );
+        }
+
+        Commands::Transfer { todo, target } => {
+            let id = workspace
+                .project(project)
+                .ok_or_else(|| TodoError::ProjectNotFound(String::from(project)))
+                .and_then(|todo_manager| todo.resolve(todo_manager))?;
+
+            let new_id = workspace.move_todo(id, project, &target)?;
+            println!("Moved to project {target} with ID {new_id}.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute a single command against the provided `TodoManager`, on behalf of
+/// the given current user.
+///
+/// # Errors
+///
+/// Returns an error if the command refers to a TODO item that doesn't exist.
+fn execute(
+    command: TodoCommands,
+    todo_manager: &mut TodoManager,
+    user: Option<&str>,
+) -> Result<(), TodoError> {
+    match command {
+        TodoCommands::Add {
+            content,
+            due,
+            scheduled,
+            priority,
+            tags,
+            parent,
+            repeat,
+        } => {
+            let id = match parent {
+                Some(parent) => {
+                    let parent_id = select_todo(todo_manager, &parent)?;
+                    todo_manager.add_subtask(parent_id, &content)?
+                }
+                None => todo_manager.add_todo(&content),
+            };
+
+            todo_manager.set_todo_due_date(id, due)?;
+            todo_manager.set_todo_scheduled_date(id, scheduled)?;
+            todo_manager.set_todo_priority(id, priority.unwrap_or_default())?;
+            todo_manager.set_todo_recurrence(id, repeat)?;
+
+            for tag in tags {
+                todo_manager.add_todo_tag(id, &tag)?;
+            }
+        }
+
+        TodoCommands::Edit {
+            todo,
+            content,
+            priority,
+        } => {
+            let id = todo.resolve(todo_manager)?;
+
+            if let Some(content) = content {
+                todo_manager.edit_todo_content(id, &content)?;
+            }
+
+            if let Some(priority) = priority {
+                todo_manager.set_todo_priority(id, priority)?;
+            }
+        }
+
+        TodoCommands::List {
+            filters,
+            all,
+            archived,
+            tree,
+            sort: keys,
+        } => {
+            if filters.mine && user.is_none() {
+                println!("No current user, set DORU_USER or \"user\" in the config file.");
+                return Ok(());
+            }
+
+            let today = Local::now().date_naive();
+
+            let listed = if all {
                 todo_manager.all_todos()
+            } else {
+                todo_manager.visible_todos(today)
+            };
+
+            let mut todos = filter_todos(todo_manager, listed, &filters, user);
+
+            let archive;
+
+            if archived {
+                // Archived TODO items go through the same queries of a
+                // manager holding just them.
+                archive =
+                    TodoManager::new(todo_manager.archived_todos().into_iter().cloned().collect())
+                        .with_workflow(todo_manager.workflow().clone());
+
+                todos.extend(filter_todos(&archive, archive.all_todos(), &filters, user));
+            }
+
+            todos.sort_by(|first, second| sort::compare(&keys, first, second));
+
+            if tree {
+                print_tree(todo_manager, &todos);
+            } else {
+                for todo in todos {
+                    println!("{}", todo.summary(todo_manager.is_done(todo)));
+                }
+            }
+        }
+
+        TodoCommands::Search { terms } => {
+            let Ok(query) = search_text(&terms).parse::<SearchQuery>();
+            let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+
+            for hit in todo_manager.search(&query) {
+                print_hit(&hit, todo_manager.is_done(hit.todo), color);
+            }
+        }
+
+        TodoCommands::Show { todo } => {
+            let id = todo.resolve(todo_manager)?;
+            let archived = todo_manager.archived_todos();
+            let found = todo_manager
+                .todo_by_id(id)
+                .or_else(|| archived.into_iter().find(|todo| todo.id() == id))
+                .ok_or(TodoError::NotFound(id))?;
+            print_details(todo_manager, found);
+        }
+
+        TodoCommands::Note {
+            todo,
+            text,
+            replace,
+        } => {
+            let id = todo.resolve(todo_manager)?;
+
+            if replace {
+                todo_manager.set_todo_notes(id, &text)?;
+            } else {
+                todo_manager.append_todo_notes(id, &text)?;
+            }
+        }
+
+        TodoCommands::Comment { todo, text } => {
+            let id = todo.resolve(todo_manager)?;
+            todo_manager.add_comment(id, &text, user)?;
+        }
+
+        TodoCommands::Status {
+            todo,
+            status,
+            reopen,
+        } => {
+            let id = todo.resolve(todo_manager)?;
+
+            if reopen {
+                todo_manager.reopen_todo(id, status)?;
+            } else {
+                todo_manager.change_todo_status(id, status)?;
+            }
+        }
+
+        TodoCommands::Tag { todo, tags } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for tag in tags {
+                todo_manager.add_todo_tag(id, &tag)?;
+            }
+        }
+
+        TodoCommands::Untag { todo, tags } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for tag in tags {
+                todo_manager.remove_todo_tag(id, &tag)?;
+            }
+        }
+
+        TodoCommands::Tags => {
+            for (tag, count) in todo_manager.all_tags() {
+                println!("#{tag:<20} {count}");
+            }
+        }
+
+        TodoCommands::Assign {
+            todo,
+            users,
+            remove,
+        } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for assignee in users {
+                if remove {
+                    todo_manager.unassign_todo(id, &assignee)?;
+                } else {
+                    todo_manager.assign_todo(id, &assignee)?;
+                }
+            }
+        }
+
+        TodoCommands::Link {
+            todo,
+            references,
+            remove,
+        } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for reference in references.into_iter().map(Reference::absolute) {
+                if remove {
+                    todo_manager.remove_todo_reference(id, &reference)?;
+                } else {
+                    todo_manager.add_todo_reference(id, reference)?;
+                }
+            }
+        }
+
+        TodoCommands::Open { todo, launch } => {
+            let id = todo.resolve(todo_manager)?;
+            let todo = todo_manager.todo_by_id(id).ok_or(TodoError::NotFound(id))?;
+
+            for reference in &todo.references {
+                match reference {
+                    Reference::Todo(other) => match todo_manager.todo_by_id(*other) {
+                        Some(other) => println!("{}", other.summary(todo_manager.is_done(other))),
+                        None if is_trashed(todo_manager, *other) => {
+                            println!("{reference} (deleted)")
+                        }
+                        None => println!("{reference} (missing)"),
+                    },
+                    _ if reference.is_missing() => println!("{reference} (missing)"),
+                    Reference::Url(url) if launch => open_externally(url),
+                    Reference::File { path, .. } if launch => {
+                        open_externally(&path.to_string_lossy())
+                    }
+                    _ => println!("{reference}"),
+                }
+            }
+        }
+
+        TodoCommands::Set { todo, fields } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for (key, value) in fields {
+                todo_manager.set_todo_field(id, &key, value)?;
+            }
+        }
+
+        TodoCommands::Unset { todo, keys } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for key in keys {
+                todo_manager.unset_todo_field(id, &key)?;
+            }
+        }
+
+        TodoCommands::Snooze { todo, when } => {
+            let id = todo.resolve(todo_manager)?;
+            todo_manager.set_todo_hidden_until(id, when)?;
+        }
+
+        TodoCommands::Checklist { todo, command } => {
+            let id = todo.resolve(todo_manager)?;
+
+            match command {
+                ChecklistCommands::Add { text } => {
+                    todo_manager.add_checklist_item(id, &text)?;
+                }
+
+                ChecklistCommands::Check { number } => {
+                    todo_manager.check_checklist_item(id, checklist_index(number))?
+                }
+
+                ChecklistCommands::Uncheck { number } => {
+                    todo_manager.uncheck_checklist_item(id, checklist_index(number))?
+                }
+
+                ChecklistCommands::Remove { number } => {
+                    todo_manager.remove_checklist_item(id, checklist_index(number))?
+                }
+            }
+        }
+
+        TodoCommands::Repeat { todo, recurrence } => {
+            let id = todo.resolve(todo_manager)?;
+
+            todo_manager.set_todo_recurrence(id, recurrence)?
+        }
+
+        TodoCommands::Depend {
+            todo,
+            dependencies,
+            remove,
+        } => {
+            let id = todo.resolve(todo_manager)?;
+
+            for dependency in dependencies {
+                let dependency_id = select_todo(todo_manager, &dependency)?;
+                if remove {
+                    todo_manager.remove_dependency(id, dependency_id)?;
+                } else {
+                    todo_manager.add_dependency(id, dependency_id)?;
+                }
+            }
+        }
+
+        TodoCommands::Move { todo, parent } => {
+            let id = todo.resolve(todo_manager)?;
+            let parent_id = parent
+                .map(|parent| select_todo(todo_manager, &parent))
+                .transpose()?;
+
+            todo_manager.move_subtree(id, parent_id)?;
+        }
+
+        TodoCommands::Delete { todo, cascade } => {
+            let id = todo.resolve(todo_manager)?;
+
+            if cascade {
+                todo_manager.delete_todo_cascade(id)?;
+            } else {
+                todo_manager.delete_todo(id)?;
+            }
+        }
+
+        TodoCommands::Archive { todo: Some(todo) } => {
+            let id = select_todo(todo_manager, &todo)?;
+            todo_manager.archive_todo(id)?;
+        }
+
+        TodoCommands::Archive { todo: None } => {
+            let archived = todo_manager.archive_done_todos();
+            println!("Archived {} TODO items.", archived.len());
+        }
+
+        TodoCommands::Trash { command } => match command {
+            TrashCommands::List => {
+                for todo in todo_manager.trashed_todos() {
+                    println!("{}", todo.summary(todo_manager.is_done(todo)));
+                }
+            }
+
+            TrashCommands::Restore { todo } => {
+                let id = todo.resolve_trashed(todo_manager)?;
+                todo_manager.restore_todo(id)?;
+            }
+
+            TrashCommands::Empty => {
+                let removed = todo_manager.empty_trash();
+                println!("Removed {removed} TODO items from the trash.");
+            }
+        },
+
+        TodoCommands::Undo => {
+            let operation = todo_manager.undo()?;
+            println!("Undone: {operation}.");
+        }
+
+        TodoCommands::Redo => {
+            let operation = todo_manager.redo()?;
+            println!("Redone: {operation}.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the id of the TODO item selected by its ID or, if the selector is not a
+/// number, by words of its content.
+///
+/// # Errors
+///
+/// Returns an error if no TODO item or several TODO items match the words.
+fn select_todo(todo_manager: &TodoManager, selector: &str) -> Result<usize, TodoError> {
+    match selector.trim().parse() {
+        Ok(id) => Ok(id),
+        Err(_) => todo_manager.find_todo(selector).map(Todo::id),
+    }
+}
+
+/// Get the name of the project with a running timer, if any.
+fn running_timer_project(workspace: &Workspace) -> Option<String> {
+    workspace
+        .project_names()
+        .into_iter()
+        .find(|name| {
+            workspace
+                .project(name)
+                .is_some_and(|todo_manager| todo_manager.running_timer().is_some())
+        })
+        .map(String::from)
+}
+
+/// Print all details of a TODO item, one per line.
+fn print_details(todo_manager: &TodoManager, todo: &Todo) {
+    println!("ID:           {}", todo.id());
+    println!("Content:      {}", todo.content);
+    println!("Status:       {}", todo.status);
+    println!("Priority:     {:?}", todo.priority);
+
+    if let Some(due) = todo.due {
+        println!("Due:          {due}");
+    }
+
+    if let Some(scheduled) = todo.scheduled {
+        println!("Scheduled:    {scheduled}");
+    }
+
+    if let Some(recurrence) = todo.recurrence {
+        println!("Repeats:      {recurrence}");
+    }
+
+    if !todo.tags.is_empty() {
+        let tags: Vec<String> = todo.tags.iter().map(|tag| format!("#{tag}")).collect();
+        println!("Tags:         {}", tags.join(" "));
+    }
+
+    if !todo.assignees.is_empty() {
+        let users: Vec<&str> = todo.assignees.iter().map(String::as_str).collect();
+        println!("Assignees:    {}", users.join(", "));
+    }
+
+    if !todo.references.is_empty() {
+        println!("References:");
+
+        for reference in &todo.references {
+            let missing = match reference {
+                Reference::Todo(id) if is_trashed(todo_manager, *id) => " (deleted)",
+                _ if reference.is_missing() || is_dangling(todo_manager, reference) => " (missing)",
+                _ => "",
             };
 
-            for todo in todos {
-                println!("{todo}");
+            println!("    {reference}{missing}");
+        }
+    }
+
+    for (key, value) in &todo.fields {
+        println!("{:<14}{value}", format!("{key}:"));
+    }
+
+    if let Some(parent) = todo.parent() {
+        println!("Parent:       {parent}");
+    }
+
+    let children = todo_manager.children(todo.id());
+    if !children.is_empty() {
+        let ids: Vec<String> = children
+            .iter()
+            .map(|child| child.id().to_string())
+            .collect();
+        println!("Subtasks:     {}", ids.join(", "));
+    }
+
+    if !todo.dependencies().is_empty() {
+        let ids: Vec<String> = todo
+            .dependencies()
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        println!("Depends on:   {}", ids.join(", "));
+    }
+
+    if let Some(created) = todo.created() {
+        println!("Created:      {}", format_time(created));
+    }
+
+    if let Some(updated) = todo.updated() {
+        println!("Updated:      {}", format_time(updated));
+    }
+
+    if let Some(completed) = todo.completed() {
+        println!("Completed:    {}", format_time(completed));
+    }
+
+    if !todo.time_entries().is_empty() {
+        let spent = format_duration(todo.time_spent(Utc::now()));
+
+        match todo.running_since() {
+            Some(since) => println!(
+                "Time spent:   {spent} (running since {})",
+                format_time(since)
+            ),
+            None => println!("Time spent:   {spent}"),
+        }
+    }
+
+    if !todo.checklist.is_empty() {
+        let (done, total) = todo.checklist_progress();
+        println!("Checklist:    {done}/{total}");
+
+        for (number, item) in (1..).zip(&todo.checklist) {
+            let tick = if item.done { "x" } else { " " };
+            println!("    [{tick}] {number}. {}", item.text);
+        }
+    }
+
+    if !todo.notes.is_empty() {
+        println!();
+
+        for line in todo.notes.lines() {
+            println!("    {line}");
+        }
+    }
+
+    if !todo.comments().is_empty() {
+        println!();
+        println!("Comments:");
+
+        for comment in todo.comments() {
+            let author = comment.author.as_deref().unwrap_or("anonymous");
+            println!("    {} {author}:", format_time(comment.time));
+
+            for line in comment.text.lines() {
+                println!("        {line}");
             }
         }
+    }
+}
 
-        Commands::Status { id, status } => todo_manager
-            .change_todo_status(id, status)
-            .unwrap_or_else(|e| println!("{e}")),
+/// Parse a date given as YYYY-MM-DD, tomorrow, or a number of days or weeks
+/// from today, such as 3d or 2w.
+fn parse_when(when: &str) -> Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    let invalid = || format!("expected YYYY-MM-DD, tomorrow, Nd or Nw, got {when:?}");
 
-        Commands::Delete { id } => todo_manager
-            .delete_todo(id)
-            .unwrap_or_else(|e| println!("{e}")),
+    if let Ok(date) = when.parse() {
+        return Ok(date);
     }
 
-    storage::JsonStorage::save(&todo_manager.all_todos(), &path).unwrap_or_else(|e| panic!("{e}"));
+    let count = |number: &str| number.parse::<i64>().map_err(|_| invalid());
+
+    let delta = if when == "tomorrow" {
+        TimeDelta::try_days(1)
+    } else if let Some(days) = when.strip_suffix('d') {
+        TimeDelta::try_days(count(days)?)
+    } else if let Some(weeks) = when.strip_suffix('w') {
+        TimeDelta::try_weeks(count(weeks)?)
+    } else {
+        return Err(invalid());
+    };
+
+    delta
+        .and_then(|delta| today.checked_add_signed(delta))
+        .ok_or_else(|| format!("{when:?} is out of the supported range of dates"))
+}
+
+/// Open a URL or a file with the default application of the system.
+fn open_externally(target: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        process::Command::new("xdg-open")
+    };
+
+    match command.arg(target).status() {
+        Ok(status) if status.success() => println!("Opened {target}"),
+        _ => println!("Failed opening {target}"),
+    }
+}
+
+/// Parse a custom field given as key=value.
+fn parse_field(field: &str) -> Result<(String, FieldValue), String> {
+    match field.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            let value = value.parse().unwrap_or_else(|never| match never {});
+            Ok((String::from(key.trim()), value))
+        }
+        _ => Err(format!("expected key=value, got {field:?}")),
+    }
+}
+
+/// Convert a checklist entry number, counted from 1, to its index.
+fn checklist_index(number: u32) -> usize {
+    number as usize - 1
+}
+
+/// Check whether a reference points to a TODO item that doesn't exist.
+fn is_dangling(todo_manager: &TodoManager, reference: &Reference) -> bool {
+    match reference {
+        Reference::Todo(id) => todo_manager.todo_by_id(*id).is_none(),
+        _ => false,
+    }
+}
+
+/// Check whether a TODO item with the provided id is in the trash.
+fn is_trashed(todo_manager: &TodoManager, id: usize) -> bool {
+    todo_manager
+        .trashed_todos()
+        .iter()
+        .any(|todo| todo.id() == id)
+}
+
+/// Join search terms given as separate arguments into the text of a
+/// `SearchQuery`, quoting the ones the shell received as a single quoted
+/// argument so that they stay a phrase.
+fn search_text(terms: &[String]) -> String {
+    let quoted: Vec<String> = terms
+        .iter()
+        .map(|term| {
+            if term.contains(char::is_whitespace) && !term.contains('"') {
+                format!("\"{term}\"")
+            } else {
+                term.clone()
+            }
+        })
+        .collect();
+
+    quoted.join(" ")
+}
+
+/// Print a TODO item found by a search, followed by the lines of its notes
+/// with matches in them. The TODO item is ticked if `done` is set and matches
+/// are highlighted if `color` is set.
+fn print_hit(hit: &SearchHit, done: bool, color: bool) {
+    let todo = hit.todo;
+    let tick = if done { "x" } else { " " };
+    let content = highlight(&todo.content, &hit.content_matches, color);
+
+    println!(
+        "[{tick}] {content} [{:<12}] (ID: {})",
+        todo.status,
+        todo.id()
+    );
+
+    let mut start = 0;
+
+    for line in todo.notes.split('\n') {
+        let end = start + line.len();
+        let matches: Vec<Range<usize>> = hit
+            .notes_matches
+            .iter()
+            .filter(|range| range.start < end && range.end > start)
+            .map(|range| range.start.max(start) - start..range.end.min(end) - start)
+            .collect();
+
+        if !matches.is_empty() {
+            println!("    {}", highlight(line.trim_end(), &matches, color));
+        }
+
+        start = end + 1;
+    }
+}
+
+/// Highlight the given byte ranges of the text in bold yellow if `color` is
+/// set, otherwise return the text as it is.
+fn highlight(text: &str, ranges: &[Range<usize>], color: bool) -> String {
+    if !color {
+        return String::from(text);
+    }
+
+    let mut highlighted = String::new();
+    let mut position = 0;
+
+    for range in ranges {
+        let start = range.start.min(text.len());
+        let end = range.end.min(text.len());
+
+        highlighted.push_str(&text[position..start]);
+        highlighted.push_str("\x1b[1;33m");
+        highlighted.push_str(&text[start..end]);
+        highlighted.push_str("\x1b[0m");
+        position = end;
+    }
+
+    highlighted.push_str(&text[position..]);
+    highlighted
+}
+
+/// Format a point in time in the local time zone, with minute precision.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// Format a length of time in hours and minutes.
+fn format_duration(duration: TimeDelta) -> String {
+    format!(
+        "{}h {:02}m",
+        duration.num_hours(),
+        duration.num_minutes() % 60
+    )
+}
+
+/// Get the point in time the given number of days ago, or the earliest
+/// supported one if it is out of range.
+fn days_ago(days: i64) -> DateTime<Utc> {
+    TimeDelta::try_days(days)
+        .and_then(|delta| Utc::now().checked_sub_signed(delta))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// Keep only the provided TODO items of the `TodoManager` that match all the
+/// filters, as selected by its queries. The current user is required by
+/// `--mine`.
+fn filter_todos<'a>(
+    todo_manager: &'a TodoManager,
+    mut todos: Vec<&'a Todo>,
+    filters: &ListFilters,
+    user: Option<&str>,
+) -> Vec<&'a Todo> {
+    let today = Local::now().date_naive();
+
+    let mut keep = |matching: Vec<&Todo>| {
+        let ids: BTreeSet<usize> = matching.iter().map(|todo| todo.id()).collect();
+        todos.retain(|todo| ids.contains(&todo.id()));
+    };
+
+    if let Some(status) = &filters.status {
+        keep(todo_manager.todos_by_status(status.clone()));
+    }
+
+    if let Some(query) = &filters.query {
+        keep(todo_manager.query(query));
+    }
+
+    if filters.overdue {
+        keep(todo_manager.overdue_todos(today));
+    }
+
+    if let Some(date) = filters.due_before {
+        keep(todo_manager.todos_due_before(date));
+    }
+
+    if let Some(priority) = filters.priority {
+        keep(todo_manager.todos_with_priority(priority));
+    }
+
+    if !filters.tags.is_empty() {
+        let tags: Vec<&str> = filters.tags.iter().map(String::as_str).collect();
+
+        if filters.any_tag {
+            keep(todo_manager.todos_with_any_tag(&tags));
+        } else {
+            keep(todo_manager.todos_with_all_tags(&tags));
+        }
+    }
+
+    if let Some(user) = user.filter(|_| filters.mine) {
+        keep(todo_manager.todos_assigned_to(user));
+    }
+
+    if let Some(assignee) = &filters.assignee {
+        keep(todo_manager.todos_assigned_to(assignee));
+    }
+
+    for (key, value) in &filters.fields {
+        keep(todo_manager.todos_by_field(key, value));
+    }
+
+    if filters.ready {
+        keep(todo_manager.ready_todos());
+    }
+
+    if let Some(days) = filters.created_within {
+        keep(todo_manager.todos_created_since(days_ago(days)));
+    }
+
+    if let Some(days) = filters.updated_within {
+        keep(todo_manager.todos_updated_since(days_ago(days)));
+    }
+
+    if let Some(days) = filters.completed_within {
+        keep(todo_manager.todos_completed_since(days_ago(days)));
+    }
+
+    todos
+}
+
+/// Print the provided TODO items indented under their parents.
+///
+/// TODO items whose parent is not among the provided ones are printed at the
+/// top level. Parent TODO items show progress rolled up from their subtasks.
+fn print_tree(todo_manager: &TodoManager, todos: &[&Todo]) {
+    let ids: BTreeSet<usize> = todos.iter().map(|todo| todo.id()).collect();
+
+    for root in todos
+        .iter()
+        .filter(|todo| todo.parent().is_none_or(|parent| !ids.contains(&parent)))
+    {
+        print_subtree(todo_manager, todos, root, 0);
+    }
+}
+
+/// Print a TODO item and its subtasks found among the provided TODO items.
+fn print_subtree(todo_manager: &TodoManager, todos: &[&Todo], todo: &Todo, depth: usize) {
+    let done = todo_manager.is_done(todo);
+    print!("{}{}", "    ".repeat(depth), todo.summary(done));
+
+    if !todo_manager.children(todo.id()).is_empty() {
+        if let Ok(progress) = todo_manager.todo_progress(todo.id()) {
+            print!(" ({:.0}%)", progress * 100.0);
+        }
+    }
+
+    println!();
+
+    for child in todos
+        .iter()
+        .filter(|child| child.parent() == Some(todo.id()))
+    {
+        print_subtree(todo_manager, todos, child, depth + 1);
+    }
 }
 
 /// Get the path to the todos file.
@@ -109,6 +1439,44 @@ fn get_todos_path() -> Result<PathBuf, Box<dyn Error>> {
     }
 }
 
+/// Get the path to the configuration file.
+///
+/// This function checks the `DORU_CONFIG` environment variable. If not set, it
+/// defaults to `~/.doru/config.json`.
+///
+/// # Errors
+///
+/// Returns an error if the user's home directory cannot be determined.
+fn get_config_path() -> Result<PathBuf, Box<dyn Error>> {
+    if let Ok(env_path) = env::var("DORU_CONFIG") {
+        Ok(PathBuf::from(env_path))
+    } else if let Some(home_dir) = dirs::home_dir() {
+        Ok(home_dir.join(".doru").join("config.json"))
+    } else {
+        Err("Unable to determine home directory".into())
+    }
+}
+
+/// Load the configuration from a JSON file.
+///
+/// A missing configuration file results in the default configuration.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid
+/// configuration.
+fn load_config(path: &Path) -> Result<Config, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let json = fs::read_to_string(path)?;
+    let config = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed parsing {}: {e}", path.display()))?;
+
+    Ok(config)
+}
+
 /// Ensure that the storage file exists.
 ///
 /// This function creates the necessary directories and file if they do not
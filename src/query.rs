@@ -0,0 +1,589 @@
+//! Query expressions filtering `Todo`s.
+//!
+//! This module contains the [`Query`] type, a parsed filter expression
+//! combining [`Condition`]s on fields of a `Todo` with `and`, `or`, `not` and
+//! parentheses. Queries are parsed from text, see [`Query::from_str`], and
+//! evaluated by
+//! [`TodoManager::query`](crate::todo_manager::TodoManager::query).
+//!
+//! # Examples
+//!
+//! ```
+//! use doru::query::{Condition, Operator, Query};
+//! use doru::todo::Todo;
+//!
+//! let query: Query = "id>10 and not content~deploy".parse().unwrap();
+//!
+//! assert_eq!(
+//!     query,
+//!     Query::And(
+//!         Box::new(Query::Condition(Condition::Id(Operator::Greater, 10))),
+//!         Box::new(Query::Not(Box::new(Query::Condition(
+//!             Condition::Content(Operator::Contains, String::from("deploy"))
+//!         ))))
+//!     )
+//! );
+//!
+//! assert!(query.matches(&Todo::new(11, "Fix the tests")));
+//! assert!(!query.matches(&Todo::new(12, "Deploy to staging")));
+//! ```
+
+use std::{iter::Peekable, str::Chars, str::FromStr};
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use thiserror::Error;
+
+use crate::todo::{Priority, Todo, TodoStatus};
+
+/// A filter expression matching Todo items.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Query {
+    /// Matches Todo items satisfying the condition.
+    Condition(Condition),
+
+    /// Matches Todo items the inner query doesn't match.
+    Not(Box<Query>),
+
+    /// Matches Todo items both queries match.
+    And(Box<Query>, Box<Query>),
+
+    /// Matches Todo items at least one of the queries matches.
+    Or(Box<Query>, Box<Query>),
+}
+
+/// A comparison of a field of a Todo item with a value.
+///
+/// Conditions on a value a Todo item doesn't have, such as the due date of a
+/// Todo item without one, never match.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Condition {
+    /// Compares the id of a Todo item.
+    Id(Operator, usize),
+
+    /// Compares the id of the parent of a Todo item.
+    Parent(Operator, usize),
+
+    /// Compares the status of a Todo item, case-insensitively.
+    Status(Operator, TodoStatus),
+
+    /// Compares the priority of a Todo item.
+    Priority(Operator, Priority),
+
+    /// Compares the content of a Todo item, case-insensitively.
+    Content(Operator, String),
+
+    /// Compares the notes of a Todo item, case-insensitively.
+    Notes(Operator, String),
+
+    /// Checks whether a Todo item is labeled with a tag.
+    Tag(Operator, String),
+
+    /// Checks whether a Todo item is assigned to a user.
+    Assignee(Operator, String),
+
+    /// Compares the due date of a Todo item.
+    Due(Operator, NaiveDate),
+
+    /// Compares the scheduled date of a Todo item.
+    Scheduled(Operator, NaiveDate),
+}
+
+/// An operator of a [`Condition`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Operator {
+    /// `:` or `=`, the field equals the value.
+    Is,
+
+    /// `!=`, the field doesn't equal the value.
+    IsNot,
+
+    /// `~`, the text field contains the value.
+    Contains,
+
+    /// `<`, the field is lower than the value.
+    Less,
+
+    /// `<=`, the field is lower than or equal to the value.
+    LessOrEqual,
+
+    /// `>`, the field is greater than the value.
+    Greater,
+
+    /// `>=`, the field is greater than or equal to the value.
+    GreaterOrEqual,
+}
+
+impl Operator {
+    /// Returns `true` if the operator holds for the given values. Only
+    /// [`Contains`](Operator::Contains) never holds, as it applies to text.
+    fn compare<T: Ord>(self, field: T, value: T) -> bool {
+        match self {
+            Operator::Is => field == value,
+            Operator::IsNot => field != value,
+            Operator::Contains => false,
+            Operator::Less => field < value,
+            Operator::LessOrEqual => field <= value,
+            Operator::Greater => field > value,
+            Operator::GreaterOrEqual => field >= value,
+        }
+    }
+
+    /// Returns `true` if the operator holds for the given texts, compared
+    /// case-insensitively.
+    fn compare_text(self, field: &str, value: &str) -> bool {
+        let field = field.to_lowercase();
+        let value = value.to_lowercase();
+
+        match self {
+            Operator::Contains => field.contains(&value),
+            _ => self.compare(field, value),
+        }
+    }
+
+    /// Returns `true` if the operator holds for a set that contains the value
+    /// or not.
+    fn compare_membership(self, contains: bool) -> bool {
+        match self {
+            Operator::IsNot => !contains,
+            _ => contains,
+        }
+    }
+}
+
+impl Query {
+    /// Returns `true` if the Todo item matches the `Query`.
+    pub fn matches(&self, todo: &Todo) -> bool {
+        match self {
+            Query::Condition(condition) => condition.matches(todo),
+            Query::Not(query) => !query.matches(todo),
+            Query::And(left, right) => left.matches(todo) && right.matches(todo),
+            Query::Or(left, right) => left.matches(todo) || right.matches(todo),
+        }
+    }
+}
+
+impl Condition {
+    /// Returns `true` if the Todo item satisfies the `Condition`.
+    pub fn matches(&self, todo: &Todo) -> bool {
+        match self {
+            Condition::Id(operator, id) => operator.compare(todo.id(), *id),
+            Condition::Parent(operator, id) => todo
+                .parent()
+                .is_some_and(|parent| operator.compare(parent, *id)),
+            Condition::Status(operator, status) => {
+                operator.compare_text(&todo.status.to_string(), &status.to_string())
+            }
+            Condition::Priority(operator, priority) => operator.compare(todo.priority, *priority),
+            Condition::Content(operator, text) => operator.compare_text(&todo.content, text),
+            Condition::Notes(operator, text) => operator.compare_text(&todo.notes, text),
+            Condition::Tag(operator, tag) => operator.compare_membership(todo.has_tag(tag)),
+            Condition::Assignee(operator, user) => {
+                operator.compare_membership(todo.is_assigned_to(user))
+            }
+            Condition::Due(operator, date) => {
+                todo.due.is_some_and(|due| operator.compare(due, *date))
+            }
+            Condition::Scheduled(operator, date) => todo
+                .scheduled
+                .is_some_and(|scheduled| operator.compare(scheduled, *date)),
+        }
+    }
+}
+
+/// Error returned when a string can't be parsed into a [`Query`].
+#[derive(Error, Debug, PartialEq)]
+#[error("Invalid query at column {column}: {reason}!")]
+pub struct ParseQueryError {
+    /// Column of the query, counted in characters from 1, where the error is.
+    pub column: usize,
+
+    /// Description of the error.
+    pub reason: String,
+}
+
+impl ParseQueryError {
+    fn new(column: usize, reason: impl Into<String>) -> Self {
+        Self {
+            column,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseQueryError;
+
+    /// Parses a [`Query`] from a filter expression.
+    ///
+    /// A condition is a field, an operator and a value, such as `id>10`.
+    /// Values containing spaces or operator characters are enclosed in
+    /// double quotes. Conditions are combined with `and`, `or` and `not`,
+    /// `and` binding tighter than `or`, and grouped with parentheses.
+    ///
+    /// - `id` and `parent` take a number, `priority` a priority from `low` to
+    ///   `urgent` and `due` and `scheduled` a date as `YYYY-MM-DD`. They are
+    ///   compared with `:` or `=`, `!=`, `<`, `<=`, `>` and `>=`.
+    /// - `content` and `notes` take text, compared case-insensitively with `:`
+    ///   or `=`, `!=` and `~`, which matches text containing the value.
+    /// - `status`, `tag` and `assignee` take a name, matched with `:` or `=`
+    ///   and `!=`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] with the column where the expression is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::query::Query;
+    ///
+    /// let query = "status:open and (tag:infra or content~\"deploy\") and id>10";
+    /// assert!(query.parse::<Query>().is_ok());
+    ///
+    /// let error = "status:open and".parse::<Query>().unwrap_err();
+    /// assert_eq!(error.column, 16);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let end = s.chars().count() + 1;
+
+        let mut parser = Parser {
+            tokens: tokens.into_iter().peekable(),
+            end,
+        };
+
+        let query = parser.parse_or()?;
+
+        match parser.tokens.next() {
+            None => Ok(query),
+            Some((column, _)) => Err(ParseQueryError::new(
+                column,
+                "expected `and`, `or` or end of query",
+            )),
+        }
+    }
+}
+
+/// A lexical unit of a query expression.
+#[derive(PartialEq, Debug)]
+enum Token {
+    OpenParen,
+    CloseParen,
+    Operator(Operator),
+    Word(String),
+    Quoted(String),
+}
+
+/// Splits a query expression into [`Token`]s, each paired with the column
+/// where it starts.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseQueryError> {
+    let mut chars = s.chars().zip(1..).peekable();
+    let mut tokens = Vec::new();
+
+    while let Some((c, column)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            ':' | '=' => Token::Operator(Operator::Is),
+            '~' => Token::Operator(Operator::Contains),
+            '!' if next_is(&mut chars, '=') => Token::Operator(Operator::IsNot),
+            '!' => return Err(ParseQueryError::new(column, "expected `=` after `!`")),
+            '<' if next_is(&mut chars, '=') => Token::Operator(Operator::LessOrEqual),
+            '<' => Token::Operator(Operator::Less),
+            '>' if next_is(&mut chars, '=') => Token::Operator(Operator::GreaterOrEqual),
+            '>' => Token::Operator(Operator::Greater),
+            '"' => Token::Quoted(quoted(&mut chars, column)?),
+            c => {
+                let mut word = String::from(c);
+
+                while let Some((c, _)) = chars.next_if(|(c, _)| !is_special(*c)) {
+                    word.push(c);
+                }
+
+                Token::Word(word)
+            }
+        };
+
+        tokens.push((column, token));
+    }
+
+    Ok(tokens)
+}
+
+type Columns<'a> = Peekable<std::iter::Zip<Chars<'a>, std::ops::RangeFrom<usize>>>;
+
+/// Consumes the next character if it is the expected one.
+fn next_is(chars: &mut Columns, expected: char) -> bool {
+    chars.next_if(|(c, _)| *c == expected).is_some()
+}
+
+/// Reads text enclosed in double quotes, the opening one at the given column
+/// already consumed. A backslash escapes the following character.
+fn quoted(chars: &mut Columns, column: usize) -> Result<String, ParseQueryError> {
+    let mut text = String::new();
+
+    loop {
+        match chars.next() {
+            Some(('"', _)) => return Ok(text),
+            Some(('\\', _)) => match chars.next() {
+                Some((c, _)) => text.push(c),
+                None => break,
+            },
+            Some((c, _)) => text.push(c),
+            None => break,
+        }
+    }
+
+    Err(ParseQueryError::new(column, "unterminated quoted value"))
+}
+
+/// Returns `true` if the character can't be a part of an unquoted word.
+fn is_special(c: char) -> bool {
+    c.is_whitespace() || "()\":=!~<>".contains(c)
+}
+
+/// Recursive descent parser building a [`Query`] from [`Token`]s.
+struct Parser<I: Iterator<Item = (usize, Token)>> {
+    tokens: Peekable<I>,
+
+    /// Column just past the end of the expression.
+    end: usize,
+}
+
+impl<I: Iterator<Item = (usize, Token)>> Parser<I> {
+    fn parse_or(&mut self) -> Result<Query, ParseQueryError> {
+        let mut query = self.parse_and()?;
+
+        while self.next_keyword("or") {
+            query = Query::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query, ParseQueryError> {
+        let mut query = self.parse_unary()?;
+
+        while self.next_keyword("and") {
+            query = Query::And(Box::new(query), Box::new(self.parse_unary()?));
+        }
+
+        Ok(query)
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, ParseQueryError> {
+        if self.next_keyword("not") {
+            return Ok(Query::Not(Box::new(self.parse_unary()?)));
+        }
+
+        match self.tokens.next() {
+            Some((_, Token::OpenParen)) => {
+                let query = self.parse_or()?;
+
+                match self.tokens.next() {
+                    Some((_, Token::CloseParen)) => Ok(query),
+                    Some((column, _)) => Err(ParseQueryError::new(column, "expected `)`")),
+                    None => Err(ParseQueryError::new(self.end, "expected `)`")),
+                }
+            }
+            Some((column, Token::Word(field))) => self.parse_condition(column, &field),
+            Some((column, _)) => Err(ParseQueryError::new(column, "expected a field or `(`")),
+            None => Err(ParseQueryError::new(self.end, "expected a field or `(`")),
+        }
+    }
+
+    fn parse_condition(&mut self, column: usize, field: &str) -> Result<Query, ParseQueryError> {
+        let field = field.to_lowercase();
+
+        let allowed = match field.as_str() {
+            "id" | "parent" | "priority" | "due" | "scheduled" => ORDERED,
+            "content" | "notes" => TEXT,
+            "status" | "tag" | "assignee" => NAME,
+            _ => {
+                return Err(ParseQueryError::new(
+                    column,
+                    format!("unknown field {field:?}"),
+                ))
+            }
+        };
+
+        let operator = match self.tokens.next() {
+            Some((_, Token::Operator(operator))) if allowed.contains(&operator) => operator,
+            Some((column, Token::Operator(_))) => {
+                return Err(ParseQueryError::new(
+                    column,
+                    format!("operator not supported by field {field:?}"),
+                ))
+            }
+            Some((column, _)) => return Err(ParseQueryError::new(column, "expected an operator")),
+            None => return Err(ParseQueryError::new(self.end, "expected an operator")),
+        };
+
+        let (value_column, value) = match self.tokens.next() {
+            Some((column, Token::Word(value) | Token::Quoted(value))) => (column, value),
+            Some((column, _)) => return Err(ParseQueryError::new(column, "expected a value")),
+            None => return Err(ParseQueryError::new(self.end, "expected a value")),
+        };
+
+        let invalid_value = |expected: &str| {
+            ParseQueryError::new(value_column, format!("expected {expected}, got {value:?}"))
+        };
+
+        let number = || value.parse().map_err(|_| invalid_value("a number"));
+        let date = || {
+            value
+                .parse()
+                .map_err(|_| invalid_value("a date (YYYY-MM-DD)"))
+        };
+
+        let condition = match field.as_str() {
+            "id" => Condition::Id(operator, number()?),
+            "parent" => Condition::Parent(operator, number()?),
+            "priority" => Condition::Priority(
+                operator,
+                Priority::from_str(&value, true).map_err(|_| invalid_value("a priority"))?,
+            ),
+            "due" => Condition::Due(operator, date()?),
+            "scheduled" => Condition::Scheduled(operator, date()?),
+            "content" => Condition::Content(operator, value),
+            "notes" => Condition::Notes(operator, value),
+            "status" => Condition::Status(
+                operator,
+                value.parse().map_err(|_| invalid_value("a status"))?,
+            ),
+            "tag" => Condition::Tag(operator, value),
+            "assignee" => Condition::Assignee(operator, value),
+            _ => unreachable!("unknown fields are rejected above"),
+        };
+
+        Ok(Query::Condition(condition))
+    }
+
+    /// Consumes the next token if it is the given keyword, in any case.
+    fn next_keyword(&mut self, keyword: &str) -> bool {
+        self.tokens
+            .next_if(|(_, token)| {
+                matches!(token, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+            })
+            .is_some()
+    }
+}
+
+/// Operators supported by fields holding ordered values.
+const ORDERED: &[Operator] = &[
+    Operator::Is,
+    Operator::IsNot,
+    Operator::Less,
+    Operator::LessOrEqual,
+    Operator::Greater,
+    Operator::GreaterOrEqual,
+];
+
+/// Operators supported by fields holding text.
+const TEXT: &[Operator] = &[Operator::Is, Operator::IsNot, Operator::Contains];
+
+/// Operators supported by fields holding names.
+const NAME: &[Operator] = &[Operator::Is, Operator::IsNot];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn condition(condition: Condition) -> Box<Query> {
+        Box::new(Query::Condition(condition))
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let query: Query = "tag:a or tag:b and not tag:c".parse().unwrap();
+
+        assert_eq!(
+            query,
+            Query::Or(
+                condition(Condition::Tag(Operator::Is, String::from("a"))),
+                Box::new(Query::And(
+                    condition(Condition::Tag(Operator::Is, String::from("b"))),
+                    Box::new(Query::Not(condition(Condition::Tag(
+                        Operator::Is,
+                        String::from("c")
+                    ))))
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_typed_values() {
+        let query: Query = r#"(priority>=HIGH) AND due<2024-12-24 and status!="Won't do""#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            query,
+            Query::And(
+                Box::new(Query::And(
+                    condition(Condition::Priority(
+                        Operator::GreaterOrEqual,
+                        Priority::High
+                    )),
+                    condition(Condition::Due(
+                        Operator::Less,
+                        NaiveDate::from_ymd_opt(2024, 12, 24).unwrap()
+                    ))
+                )),
+                condition(Condition::Status(
+                    Operator::IsNot,
+                    TodoStatus::Custom(String::from("Won't do"))
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_column() {
+        let cases = [
+            ("", 1),
+            ("id>", 4),
+            ("id>ten", 4),
+            ("size>10", 1),
+            ("content<abc", 8),
+            ("tag:a tag:b", 7),
+            ("(tag:a or tag:b", 16),
+            ("content~\"abc", 9),
+            ("id!10", 3),
+            ("not and", 5),
+        ];
+
+        for (query, column) in cases {
+            let error = query.parse::<Query>().unwrap_err();
+            assert_eq!(error.column, column, "{query}: {error}");
+        }
+    }
+
+    #[test]
+    fn conditions_on_missing_values_never_match() {
+        let todo = Todo::new(1, "Lorem");
+
+        for query in ["due!=2024-01-01", "parent!=2", "scheduled<2100-01-01"] {
+            assert!(!query.parse::<Query>().unwrap().matches(&todo), "{query}");
+        }
+
+        assert!("tag!=ipsum".parse::<Query>().unwrap().matches(&todo));
+    }
+
+    #[test]
+    fn text_conditions_ignore_case() {
+        let mut todo = Todo::new(1, "Deploy to Staging");
+        todo.status = TodoStatus::InProgress;
+
+        for query in [
+            "content~staging",
+            "content:\"deploy to staging\"",
+            "status:inprogress",
+        ] {
+            assert!(query.parse::<Query>().unwrap().matches(&todo), "{query}");
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Rules for repeating `Todo`s.
+//!
+//! This module contains the [`Recurrence`] enum describing how often a `Todo`
+//! repeats. When a repeating `Todo` is done, the
+//! [`TodoManager`](crate::todo_manager::TodoManager) creates its next
+//! occurrence, due on the date computed by [`Recurrence::next_due`].
+//!
+//! # Examples
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use doru::recurrence::Recurrence;
+//!
+//! let recurrence: Recurrence = "weekly".parse().unwrap();
+//!
+//! let due = NaiveDate::from_ymd_opt(2024, 12, 2);
+//! let done_on = NaiveDate::from_ymd_opt(2024, 12, 4).unwrap();
+//!
+//! assert_eq!(
+//!     recurrence.next_due(due, done_on),
+//!     NaiveDate::from_ymd_opt(2024, 12, 9)
+//! );
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{Datelike, Months, NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Rule describing how often a Todo item repeats.
+///
+/// All rules except [`AfterCompletion`](Recurrence::AfterCompletion) count
+/// from the due date of the finished occurrence, or from the day it was done
+/// if it had no due date, skipping occurrences due by the day it was done.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Recurrence {
+    /// Repeats every day.
+    Daily,
+
+    /// Repeats every week.
+    Weekly,
+
+    /// Repeats every month, on the same day of the month as the finished
+    /// occurrence, see [`anchored`](Recurrence::anchored).
+    Monthly,
+
+    /// Repeats every month on the given day of the month, or on the last day
+    /// of months shorter than that.
+    MonthlyOn(u32),
+
+    /// Repeats every given number of days.
+    EveryDays(u32),
+
+    /// Repeats the given number of days after the previous occurrence is done.
+    AfterCompletion(u32),
+}
+
+impl Recurrence {
+    /// Returns due date of the next occurrence of a Todo item with the
+    /// provided due date, done on the provided day, or [`None`] if the date
+    /// would be out of the range of [`NaiveDate`].
+    ///
+    /// The next occurrence is always due after the day the Todo item was
+    /// done, so finishing an overdue Todo item skips the occurrences it
+    /// missed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use doru::recurrence::Recurrence;
+    ///
+    /// let due = NaiveDate::from_ymd_opt(2024, 12, 2);
+    /// let done_on = NaiveDate::from_ymd_opt(2024, 12, 4).unwrap();
+    ///
+    /// assert_eq!(
+    ///     Recurrence::EveryDays(3).next_due(due, done_on),
+    ///     NaiveDate::from_ymd_opt(2024, 12, 5)
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Recurrence::AfterCompletion(3).next_due(due, done_on),
+    ///     NaiveDate::from_ymd_opt(2024, 12, 7)
+    /// );
+    ///
+    /// let overdue = NaiveDate::from_ymd_opt(2024, 11, 25);
+    /// assert_eq!(
+    ///     Recurrence::Weekly.next_due(overdue, done_on),
+    ///     NaiveDate::from_ymd_opt(2024, 12, 9)
+    /// );
+    /// ```
+    pub fn next_due(&self, due: Option<NaiveDate>, done_on: NaiveDate) -> Option<NaiveDate> {
+        let base = due.unwrap_or(done_on);
+        let rule = self.anchored(base);
+
+        let mut next = rule.after(base, done_on)?;
+
+        while next <= done_on {
+            next = rule.after(next, done_on)?;
+        }
+
+        Some(next)
+    }
+
+    /// Returns the date of the occurrence following one on `base` of a Todo
+    /// item done on the provided day.
+    fn after(&self, base: NaiveDate, done_on: NaiveDate) -> Option<NaiveDate> {
+        match *self {
+            Recurrence::Daily => base.checked_add_signed(TimeDelta::days(1)),
+            Recurrence::Weekly => base.checked_add_signed(TimeDelta::weeks(1)),
+            Recurrence::Monthly => next_month_on(base, base.day()),
+            Recurrence::MonthlyOn(day) => next_month_on(base, day),
+            Recurrence::EveryDays(days) => {
+                base.checked_add_signed(TimeDelta::try_days(days.into())?)
+            }
+            Recurrence::AfterCompletion(days) => {
+                done_on.checked_add_signed(TimeDelta::try_days(days.into())?)
+            }
+        }
+    }
+
+    /// Returns the rule the next occurrence of a Todo item repeats by, given
+    /// the date the finished occurrence counted from.
+    ///
+    /// [`Monthly`](Recurrence::Monthly) becomes
+    /// [`MonthlyOn`](Recurrence::MonthlyOn) the day of that date, so that
+    /// occurrences clamped to the end of shorter months return to the
+    /// original day afterwards. Other rules are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use doru::recurrence::Recurrence;
+    ///
+    /// let due = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+    /// let recurrence = Recurrence::Monthly.anchored(due);
+    /// assert_eq!(recurrence, Recurrence::MonthlyOn(31));
+    ///
+    /// let due = NaiveDate::from_ymd_opt(2024, 2, 29);
+    /// assert_eq!(
+    ///     recurrence.next_due(due, due.unwrap()),
+    ///     NaiveDate::from_ymd_opt(2024, 3, 31)
+    /// );
+    /// ```
+    pub fn anchored(self, base: NaiveDate) -> Recurrence {
+        match self {
+            Recurrence::Monthly => Recurrence::MonthlyOn(base.day()),
+            other => other,
+        }
+    }
+}
+
+/// Returns the date a month after the provided one on the provided day of the
+/// month, or on the last day of the month if it is shorter.
+fn next_month_on(date: NaiveDate, day: u32) -> Option<NaiveDate> {
+    let first = date.with_day(1)?.checked_add_months(Months::new(1))?;
+    let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+
+    first.with_day(day.min(last.day()))
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly => write!(f, "weekly"),
+            Recurrence::Monthly => write!(f, "monthly"),
+            Recurrence::MonthlyOn(day) => write!(f, "monthly:{day}"),
+            Recurrence::EveryDays(days) => write!(f, "every:{days}"),
+            Recurrence::AfterCompletion(days) => write!(f, "after:{days}"),
+        }
+    }
+}
+
+/// Error returned when a string can't be parsed into a [`Recurrence`].
+#[derive(Error, Debug, PartialEq)]
+#[error(
+    "Invalid recurrence {0:?}, expected daily, weekly, monthly, monthly:DAY, every:N or after:N!"
+)]
+pub struct ParseRecurrenceError(String);
+
+impl FromStr for Recurrence {
+    type Err = ParseRecurrenceError;
+
+    /// Parses a [`Recurrence`] from its [`Display`] form: `daily`, `weekly`,
+    /// `monthly`, `monthly:DAY`, `every:N` or `after:N`, where `DAY` is a day
+    /// of the month and `N` is a positive number of days.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRecurrenceError(String::from(s));
+
+        let days = |value: &str| match value.parse() {
+            Ok(0) | Err(_) => Err(error()),
+            Ok(days) => Ok(days),
+        };
+
+        match s.trim().to_lowercase().as_str() {
+            "daily" => Ok(Recurrence::Daily),
+            "weekly" => Ok(Recurrence::Weekly),
+            "monthly" => Ok(Recurrence::Monthly),
+            other => match other.split_once(':') {
+                Some(("monthly", value)) => match value.parse() {
+                    Ok(day @ 1..=31) => Ok(Recurrence::MonthlyOn(day)),
+                    _ => Err(error()),
+                },
+                Some(("every", value)) => Ok(Recurrence::EveryDays(days(value)?)),
+                Some(("after", value)) => Ok(Recurrence::AfterCompletion(days(value)?)),
+                _ => Err(error()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn next_due_without_due_date_counts_from_completion() {
+        let next = Recurrence::Daily.next_due(None, date(12, 4));
+        assert_eq!(next, Some(date(12, 5)));
+    }
+
+    #[test]
+    fn next_due_of_overdue_todo_is_after_completion() {
+        let next = Recurrence::Weekly.next_due(Some(date(12, 2)), date(12, 23));
+        assert_eq!(next, Some(date(12, 30)));
+
+        let next = Recurrence::Monthly.next_due(Some(date(1, 31)), date(3, 5));
+        assert_eq!(next, Some(date(3, 31)));
+    }
+
+    #[test]
+    fn monthly_next_due_clamps_to_month_end() {
+        let next = Recurrence::Monthly.next_due(Some(date(1, 31)), date(2, 1));
+        assert_eq!(next, Some(date(2, 29)));
+    }
+
+    #[test]
+    fn anchored_monthly_returns_to_original_day() {
+        let mut due = date(1, 31);
+        let mut recurrence = Recurrence::Monthly;
+        let mut occurrences = vec![];
+
+        for _ in 0..3 {
+            let next = recurrence.next_due(Some(due), due).unwrap();
+            recurrence = recurrence.anchored(due);
+            due = next;
+            occurrences.push(due);
+        }
+
+        assert_eq!(occurrences, vec![date(2, 29), date(3, 31), date(4, 30)]);
+        assert_eq!(recurrence, Recurrence::MonthlyOn(31));
+    }
+
+    #[test]
+    fn next_due_out_of_range_is_none() {
+        let every: Recurrence = "every:4000000000".parse().unwrap();
+
+        assert_eq!(every.next_due(Some(date(12, 4)), date(12, 4)), None);
+        assert_eq!(
+            Recurrence::Daily.next_due(Some(NaiveDate::MAX), date(12, 4)),
+            None
+        );
+    }
+
+    #[test]
+    fn display_round_trips_through_parse() {
+        let rules = [
+            Recurrence::Daily,
+            Recurrence::Weekly,
+            Recurrence::Monthly,
+            Recurrence::MonthlyOn(31),
+            Recurrence::EveryDays(3),
+            Recurrence::AfterCompletion(14),
+        ];
+
+        for rule in rules {
+            assert_eq!(rule.to_string().parse(), Ok(rule));
+        }
+    }
+
+    #[test]
+    fn parse_invalid_recurrence_fails() {
+        for invalid in [
+            "yearly",
+            "every:0",
+            "after:x",
+            "every",
+            "every:",
+            "monthly:0",
+            "monthly:32",
+        ] {
+            assert_eq!(
+                invalid.parse::<Recurrence>(),
+                Err(ParseRecurrenceError(String::from(invalid)))
+            );
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! References from `Todo`s to related resources.
+//!
+//! This module contains the [`Reference`] enum describing a link a `Todo` can
+//! carry to a web page, a local file, optionally at a given line, or another
+//! `Todo`. References are parsed from and displayed as text, see
+//! [`Reference::from_str`].
+//!
+//! # Examples
+//!
+//! ```
+//! use doru::reference::Reference;
+//! use std::path::PathBuf;
+//!
+//! let reference: Reference = "src/main.rs:42".parse().unwrap();
+//!
+//! assert_eq!(
+//!     reference,
+//!     Reference::File {
+//!         path: PathBuf::from("src/main.rs"),
+//!         line: Some(42),
+//!     }
+//! );
+//! assert_eq!(reference.to_string(), "src/main.rs:42");
+//! ```
+
+use std::{
+    fmt::Display,
+    path::{self, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A typed reference from a Todo item to a related resource.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reference {
+    /// A web address.
+    Url(String),
+
+    /// A local file, optionally pointing at a line, counted from 1.
+    File {
+        path: PathBuf,
+
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        line: Option<u32>,
+    },
+
+    /// Another Todo item, by its id.
+    Todo(usize),
+}
+
+impl Reference {
+    /// Returns `true` if the reference points to a local file that doesn't
+    /// exist. Other references are never missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::reference::Reference;
+    ///
+    /// let missing: Reference = "/nonexistent/file.txt".parse().unwrap();
+    /// assert!(missing.is_missing());
+    ///
+    /// let url: Reference = "https://example.com".parse().unwrap();
+    /// assert!(!url.is_missing());
+    /// ```
+    pub fn is_missing(&self) -> bool {
+        match self {
+            Reference::File { path, .. } => !path.exists(),
+            _ => false,
+        }
+    }
+
+    /// Returns the reference with a relative file path made absolute against
+    /// the current working directory, so that it points at the same file
+    /// from anywhere. Other references are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::reference::Reference;
+    /// use std::env;
+    ///
+    /// let reference: Reference = "src/main.rs:42".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     reference.absolute(),
+    ///     Reference::File {
+    ///         path: env::current_dir().unwrap().join("src/main.rs"),
+    ///         line: Some(42),
+    ///     }
+    /// );
+    /// ```
+    pub fn absolute(self) -> Reference {
+        match self {
+            Reference::File { path, line } => Reference::File {
+                path: path::absolute(&path).unwrap_or(path),
+                line,
+            },
+            other => other,
+        }
+    }
+}
+
+impl Display for Reference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reference::Url(url) => write!(f, "{url}"),
+            Reference::File { path, line: None } => write!(f, "{}", path.display()),
+            Reference::File {
+                path,
+                line: Some(line),
+            } => write!(f, "{}:{line}", path.display()),
+            Reference::Todo(id) => write!(f, "#{id}"),
+        }
+    }
+}
+
+/// Error returned when a string can't be parsed into a [`Reference`].
+#[derive(Error, Debug, PartialEq)]
+#[error("Invalid reference {0:?}, expected a URL, a file path or #ID!")]
+pub struct ParseReferenceError(String);
+
+impl FromStr for Reference {
+    type Err = ParseReferenceError;
+
+    /// Parses a [`Reference`] from its [`Display`] form.
+    ///
+    /// Text containing `://` is a [`Url`](Reference::Url), `#ID` is a
+    /// [`Todo`](Reference::Todo) and anything else is a
+    /// [`File`](Reference::File) path, pointing at a line if it ends with
+    /// `:LINE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseReferenceError(String::from(s));
+        let text = s.trim();
+
+        if text.is_empty() {
+            return Err(error());
+        }
+
+        if text.contains("://") {
+            return Ok(Reference::Url(String::from(text)));
+        }
+
+        if let Some(id) = text.strip_prefix('#') {
+            return id.parse().map(Reference::Todo).map_err(|_| error());
+        }
+
+        let with_line = text
+            .rsplit_once(':')
+            .map(|(path, line)| (path, line.parse::<u32>()));
+
+        let reference = match with_line {
+            Some((path, Ok(line))) if !path.is_empty() && line > 0 => Reference::File {
+                path: PathBuf::from(path),
+                line: Some(line),
+            },
+            _ => Reference::File {
+                path: PathBuf::from(text),
+                line: None,
+            },
+        };
+
+        Ok(reference)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_round_trips_through_parse() {
+        let references = [
+            Reference::Url(String::from("https://example.com/a?b=c:1")),
+            Reference::File {
+                path: PathBuf::from("notes.md"),
+                line: None,
+            },
+            Reference::File {
+                path: PathBuf::from("/tmp/lorem.rs"),
+                line: Some(7),
+            },
+            Reference::Todo(12),
+        ];
+
+        for reference in references {
+            assert_eq!(reference.to_string().parse(), Ok(reference));
+        }
+    }
+
+    #[test]
+    fn absolute_keeps_absolute_paths_and_other_references() {
+        let references = [
+            Reference::Url(String::from("https://example.com/lorem")),
+            Reference::File {
+                path: PathBuf::from("/tmp/lorem.rs"),
+                line: Some(7),
+            },
+            Reference::Todo(12),
+        ];
+
+        for reference in references {
+            assert_eq!(reference.clone().absolute(), reference);
+        }
+    }
+
+    #[test]
+    fn absolute_resolves_relative_paths_against_working_directory() {
+        let reference: Reference = "../lorem.md".parse().unwrap();
+
+        let Reference::File { path, line: None } = reference.absolute() else {
+            panic!("Expected a file reference");
+        };
+
+        assert!(path.is_absolute());
+        assert_eq!(path, std::env::current_dir().unwrap().join("../lorem.md"));
+    }
+
+    #[test]
+    fn parse_invalid_reference_fails() {
+        for invalid in ["", "  ", "#", "#lorem"] {
+            assert_eq!(
+                invalid.parse::<Reference>(),
+                Err(ParseReferenceError(String::from(invalid)))
+            );
+        }
+    }
+}
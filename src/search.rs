@@ -0,0 +1,469 @@
+//! Full-text search over `Todo`s.
+//!
+//! This module contains the [`SearchQuery`] type, a parsed list of words and
+//! quoted phrases to look up in the content and notes of `Todo`s, and the
+//! [`SearchHit`] type describing where a `Todo` matches it. Matching ignores
+//! case and diacritics, so `cafe` finds `Café`. See
+//! [`TodoManager::search`](crate::todo_manager::TodoManager::search).
+//!
+//! A [`SearchQuery`] also selects a `Todo` by its content, tolerating
+//! abbreviations and typos, by the [`Closeness`] of the content to it. See
+//! [`TodoManager::find_todo`](crate::todo_manager::TodoManager::find_todo).
+//!
+//! # Examples
+//!
+//! ```
+//! use doru::search::SearchQuery;
+//!
+//! let query: SearchQuery = "\"deploy to\" STAG".parse().unwrap();
+//!
+//! assert_eq!(
+//!     query.find("Deploy to staging, then deploy to prod"),
+//!     vec![0..9, 10..17, 24..33]
+//! );
+//! assert_eq!(query.find("Staging deploy"), vec![0..7]);
+//! ```
+
+use std::{convert::Infallible, ops::Range, str::FromStr};
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::todo::Todo;
+
+/// Weight of a match in the content of a Todo item, relative to a match in
+/// its notes.
+const CONTENT_WEIGHT: usize = 3;
+
+/// A full-text search query: words and phrases that all have to be found.
+///
+/// A word matches any word starting with it, ignoring case and diacritics. A
+/// phrase matches consecutive words matching its words in order.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct SearchQuery {
+    phrases: Vec<Vec<String>>,
+}
+
+/// How closely a text matches a [`SearchQuery`], from the loosest to the
+/// closest. Closeness of the same kind is compared by its score, the number
+/// of words of the query matched more closely than the kind requires.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum Closeness {
+    /// Every word of the query is close to a word of the text: it starts the
+    /// word, its letters appear in the word in order, from the first one,
+    /// such as `lrn` in `learn`, or it differs from the word by a typo.
+    /// Scored by the words of the query starting a word of the text.
+    Fuzzy(usize),
+
+    /// All words and phrases of the query are found in the text, see
+    /// [`SearchQuery::matches_text`]. Scored by the words of the query equal
+    /// to a whole word of the text.
+    Words(usize),
+
+    /// The text consists of the words of the query, in the same order.
+    Exact,
+}
+
+/// A Todo item found by a [`SearchQuery`], with the byte ranges of the
+/// matches in its content and notes.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SearchHit<'a> {
+    /// The found Todo item.
+    pub todo: &'a Todo,
+
+    /// Relevance of the Todo item, the higher the more relevant.
+    pub score: usize,
+
+    /// Sorted, non-overlapping ranges of matches in the content.
+    pub content_matches: Vec<Range<usize>>,
+
+    /// Sorted, non-overlapping ranges of matches in the notes.
+    pub notes_matches: Vec<Range<usize>>,
+}
+
+impl SearchQuery {
+    /// Returns `true` if the query has no words to look up.
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+
+    /// Returns the sorted, non-overlapping byte ranges of all matches of any
+    /// of the words and phrases in the text.
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let words = words(text);
+
+        let matches = self
+            .phrases
+            .iter()
+            .flat_map(|phrase| find_phrase(phrase, &words))
+            .map(|(first, last)| words[first].range.start..words[last].range.end)
+            .collect();
+
+        merge(matches)
+    }
+
+    /// Returns `true` if all the words and phrases are found in the text.
+    pub fn matches_text(&self, text: &str) -> bool {
+        let words = words(text);
+
+        !self.is_empty()
+            && self
+                .phrases
+                .iter()
+                .all(|phrase| !find_phrase(phrase, &words).is_empty())
+    }
+
+    /// Returns how closely the text matches the query, or [`None`] if some
+    /// word of the query is not close to any word of the text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::search::{Closeness, SearchQuery};
+    ///
+    /// let closeness = |query: &str| {
+    ///     query
+    ///         .parse::<SearchQuery>()
+    ///         .unwrap()
+    ///         .closeness("Learn Rust")
+    /// };
+    ///
+    /// assert_eq!(closeness("learn RUST"), Some(Closeness::Exact));
+    /// assert_eq!(closeness("rust lea"), Some(Closeness::Words(1)));
+    /// assert_eq!(closeness("lrn rst"), Some(Closeness::Fuzzy(0)));
+    /// assert_eq!(closeness("learn ruts"), Some(Closeness::Fuzzy(1)));
+    /// assert_eq!(closeness("learn go"), None);
+    /// ```
+    pub fn closeness(&self, text: &str) -> Option<Closeness> {
+        let words = words(text);
+        let terms: Vec<&String> = self.phrases.iter().flatten().collect();
+
+        let is_exact = terms.len() == words.len()
+            && terms
+                .iter()
+                .zip(&words)
+                .all(|(term, word)| **term == word.folded);
+
+        let count = |matching: fn(&str, &str) -> bool| {
+            terms
+                .iter()
+                .filter(|term| words.iter().any(|word| matching(term, &word.folded)))
+                .count()
+        };
+
+        if self.is_empty() {
+            None
+        } else if is_exact {
+            Some(Closeness::Exact)
+        } else if self.matches_text(text) {
+            Some(Closeness::Words(count(|term, word| term == word)))
+        } else if count(is_close) == terms.len() {
+            Some(Closeness::Fuzzy(count(|term, word| word.starts_with(term))))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a [`SearchHit`] if all the words and phrases are found in the
+    /// content or notes of the Todo item, otherwise returns [`None`].
+    ///
+    /// The score of a hit counts the matches of each word and phrase, longer
+    /// phrases and matches of whole words counting more and matches in the
+    /// content counting more than matches in the notes.
+    pub fn matches<'a>(&self, todo: &'a Todo) -> Option<SearchHit<'a>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let content = words(&todo.content);
+        let notes = words(&todo.notes);
+        let mut score = 0;
+
+        for phrase in &self.phrases {
+            let content_score = phrase_score(phrase, &content);
+            let notes_score = phrase_score(phrase, &notes);
+
+            if content_score + notes_score == 0 {
+                return None;
+            }
+
+            score += CONTENT_WEIGHT * content_score + notes_score;
+        }
+
+        Some(SearchHit {
+            todo,
+            score,
+            content_matches: self.find(&todo.content),
+            notes_matches: self.find(&todo.notes),
+        })
+    }
+}
+
+impl FromStr for SearchQuery {
+    type Err = Infallible;
+
+    /// Parses a [`SearchQuery`] from words separated by whitespace and
+    /// phrases enclosed in double quotes. Punctuation separates words too, so
+    /// an unquoted `e-mail` is a phrase of two words.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let phrases = s
+            .split('"')
+            .enumerate()
+            .flat_map(|(index, part)| {
+                if index % 2 == 1 {
+                    vec![part]
+                } else {
+                    part.split_whitespace().collect()
+                }
+            })
+            .map(|phrase| words(phrase).into_iter().map(|word| word.folded).collect())
+            .filter(|phrase: &Vec<String>| !phrase.is_empty())
+            .collect();
+
+        Ok(Self { phrases })
+    }
+}
+
+/// A word of a text, folded for matching, with its byte range in the text.
+struct Word {
+    folded: String,
+    range: Range<usize>,
+}
+
+/// Splits a text into words, runs of alphanumeric characters and the marks
+/// combined with them.
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        let in_word = c.is_alphanumeric() || is_combining_mark(c);
+
+        match start {
+            None if in_word => start = Some(index),
+            Some(first) if !in_word => {
+                words.push(Word {
+                    folded: fold(&text[first..index]),
+                    range: first..index,
+                });
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    words
+}
+
+/// Folds a text for matching, decomposing it, dropping diacritics and
+/// lowering its case.
+fn fold(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Returns `true` if the folded term starts the folded word, abbreviates it,
+/// or differs from it or its start by a typo: one edit in terms of four to
+/// seven letters, two in longer terms.
+fn is_close(term: &str, word: &str) -> bool {
+    let allowed = match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    };
+
+    let start: String = word.chars().take(term.chars().count()).collect();
+
+    word.starts_with(term)
+        || is_abbreviation(term, word)
+        || (allowed > 0 && distance(term, &start).min(distance(term, word)) <= allowed)
+}
+
+/// Returns `true` if the term starts with the first letter of the word and
+/// all its letters appear in the word in the same order.
+fn is_abbreviation(term: &str, word: &str) -> bool {
+    let mut letters = word.chars();
+
+    term.chars().next() == word.chars().next()
+        && term.chars().all(|c| letters.any(|letter| letter == c))
+}
+
+/// Returns the number of letters inserted, deleted, replaced or swapped with
+/// their neighbours needed to turn one text into the other.
+fn distance(first: &str, second: &str) -> usize {
+    let first: Vec<char> = first.chars().collect();
+    let second: Vec<char> = second.chars().collect();
+
+    // Distances between prefixes of the texts, by their lengths.
+    let mut distances = vec![vec![0; second.len() + 1]; first.len() + 1];
+
+    for (length, row) in distances.iter_mut().enumerate() {
+        row[0] = length;
+    }
+
+    for (length, cell) in distances[0].iter_mut().enumerate() {
+        *cell = length;
+    }
+
+    for i in 1..=first.len() {
+        for j in 1..=second.len() {
+            let replace = usize::from(first[i - 1] != second[j - 1]);
+
+            let mut best = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + replace);
+
+            if i > 1 && j > 1 && first[i - 1] == second[j - 2] && first[i - 2] == second[j - 1] {
+                best = best.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = best;
+        }
+    }
+
+    distances[first.len()][second.len()]
+}
+
+/// Returns indexes of the first and last word of each non-overlapping match of
+/// the phrase in the words.
+fn find_phrase(phrase: &[String], words: &[Word]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut first = 0;
+
+    while first + phrase.len() <= words.len() {
+        let found = phrase
+            .iter()
+            .zip(&words[first..])
+            .all(|(term, word)| word.folded.starts_with(term.as_str()));
+
+        if found {
+            matches.push((first, first + phrase.len() - 1));
+            first += phrase.len();
+        } else {
+            first += 1;
+        }
+    }
+
+    matches
+}
+
+/// Returns the relevance of the phrase in the words, zero if it is not found.
+fn phrase_score(phrase: &[String], words: &[Word]) -> usize {
+    find_phrase(phrase, words)
+        .into_iter()
+        .map(|(first, last)| {
+            let whole_words = phrase
+                .iter()
+                .zip(&words[first..=last])
+                .all(|(term, word)| word.folded == *term);
+
+            if whole_words {
+                2 * phrase.len()
+            } else {
+                phrase.len()
+            }
+        })
+        .sum()
+}
+
+/// Sorts the ranges and merges the overlapping ones.
+fn merge(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::new();
+
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn query(text: &str) -> SearchQuery {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn matching_ignores_case_and_diacritics() {
+        let text = "Réunion at the CAFÉ";
+
+        assert_eq!(query("cafe reunion").find(text), vec![0..8, 16..21]);
+        assert_eq!(query("ÇAFÉ").find(text), vec![16..21]);
+    }
+
+    #[test]
+    fn phrase_matches_consecutive_words_only() {
+        let text = "deploy the app, then test staging";
+
+        assert!(query("\"deploy staging\"").find(text).is_empty());
+        assert_eq!(query("\"the app\"").find(text), vec![7..14]);
+        assert_eq!(query("app-then").find(text), vec![11..20]);
+    }
+
+    #[test]
+    fn all_words_have_to_be_found() {
+        let mut todo = Todo::new(1, "Deploy to staging");
+        todo.notes = String::from("Ask the infra team first.");
+
+        assert!(query("deploy infra").matches(&todo).is_some());
+        assert!(query("deploy prod").matches(&todo).is_none());
+        assert!(query("").matches(&todo).is_none());
+
+        assert!(query("stag DEP").matches_text(&todo.content));
+        assert!(!query("deploy infra").matches_text(&todo.content));
+        assert!(!query("").matches_text(&todo.content));
+    }
+
+    #[test]
+    fn whole_words_in_content_score_higher() {
+        let todo = Todo::new(1, "Deploy");
+        let mut in_notes = Todo::new(2, "Release");
+        in_notes.notes = String::from("deploy");
+
+        let exact = query("deploy").matches(&todo).unwrap();
+        let prefix = query("dep").matches(&todo).unwrap();
+        let notes = query("deploy").matches(&in_notes).unwrap();
+
+        assert!(exact.score > prefix.score);
+        assert!(exact.score > notes.score);
+    }
+
+    #[test]
+    fn closeness_tolerates_abbreviations_and_typos() {
+        let text = "Deploy to staging";
+
+        assert_eq!(query("dpl stg").closeness(text), Some(Closeness::Fuzzy(0)));
+        assert_eq!(query("deplyo").closeness(text), Some(Closeness::Fuzzy(0)));
+        assert_eq!(
+            query("deploy stagign").closeness(text),
+            Some(Closeness::Fuzzy(1))
+        );
+        assert_eq!(query("to stg").closeness(text), Some(Closeness::Fuzzy(1)));
+        assert_eq!(query("tp").closeness(text), None);
+        assert_eq!(query("stagnation").closeness(text), None);
+        assert!(Closeness::Words(0) > Closeness::Fuzzy(2));
+    }
+
+    #[test]
+    fn distance_counts_edits_and_swaps() {
+        assert_eq!(distance("staging", "staging"), 0);
+        assert_eq!(distance("stagign", "staging"), 1);
+        assert_eq!(distance("stging", "staging"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn overlapping_matches_are_merged() {
+        assert_eq!(query("dep deploy").find("deploy"), vec![0..6]);
+        assert_eq!(merge(vec![4..6, 0..2, 1..3]), vec![0..3, 4..6]);
+    }
+}
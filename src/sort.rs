@@ -0,0 +1,311 @@
+//! Ordering of `Todo`s by several keys.
+//!
+//! This module contains the [`SortKey`] type, pairing a [`SortField`] of a
+//! `Todo` with a [`Direction`]. A list of keys orders `Todo`s by the first
+//! key, then `Todo`s equal in it by the second one, and so on, see
+//! [`compare`]. Keys are parsed from text, see [`SortKey::from_str`].
+//!
+//! # Examples
+//!
+//! ```
+//! use doru::sort::{self, SortKey};
+//! use doru::todo::{Todo, TodoStatus};
+//!
+//! let mut done = Todo::new(1, "Learn Rust");
+//! done.status = TodoStatus::Done;
+//! let open = Todo::new(2, "Learn to cook");
+//! let other_open = Todo::new(3, "Learn to dance");
+//!
+//! let keys: Vec<SortKey> = ["status", "-id"]
+//!     .iter()
+//!     .map(|key| key.parse().unwrap())
+//!     .collect();
+//!
+//! let mut todos = vec![&done, &open, &other_open];
+//! todos.sort_by(|a, b| sort::compare(&keys, a, b));
+//!
+//! assert_eq!(todos, vec![&other_open, &open, &done]);
+//! ```
+
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+use crate::todo::{Todo, TodoStatus};
+
+/// A field of a Todo item that Todo items can be ordered by.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SortField {
+    /// The id, in order of creation.
+    Id,
+
+    /// The id of the parent.
+    Parent,
+
+    /// The status, ordered from [`Open`](TodoStatus::Open) through
+    /// [`InProgress`](TodoStatus::InProgress) and custom statuses, by their
+    /// names, to [`Done`](TodoStatus::Done).
+    Status,
+
+    /// The priority, from the least to the most important.
+    Priority,
+
+    /// The content, alphabetically and case-insensitively.
+    Content,
+
+    /// The due date.
+    Due,
+
+    /// The scheduled date.
+    Scheduled,
+
+    /// The time of creation.
+    Created,
+
+    /// The time of the last modification.
+    Updated,
+
+    /// The time of completion.
+    Completed,
+}
+
+impl SortField {
+    /// All fields, in the order they are listed in help texts.
+    pub const ALL: [SortField; 10] = [
+        SortField::Id,
+        SortField::Parent,
+        SortField::Status,
+        SortField::Priority,
+        SortField::Content,
+        SortField::Due,
+        SortField::Scheduled,
+        SortField::Created,
+        SortField::Updated,
+        SortField::Completed,
+    ];
+
+    /// Returns the name the field is parsed from.
+    pub fn name(self) -> &'static str {
+        match self {
+            SortField::Id => "id",
+            SortField::Parent => "parent",
+            SortField::Status => "status",
+            SortField::Priority => "priority",
+            SortField::Content => "content",
+            SortField::Due => "due",
+            SortField::Scheduled => "scheduled",
+            SortField::Created => "created",
+            SortField::Updated => "updated",
+            SortField::Completed => "completed",
+        }
+    }
+}
+
+/// Returns a key ordering statuses from open to done, custom statuses by
+/// their names in between.
+fn status_rank(status: &TodoStatus) -> (u8, String) {
+    match status {
+        TodoStatus::Open => (0, String::new()),
+        TodoStatus::InProgress => (1, String::new()),
+        TodoStatus::Custom(name) => (2, name.to_lowercase()),
+        TodoStatus::Done => (3, String::new()),
+    }
+}
+
+/// Direction of ordering by a [`SortField`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Direction {
+    /// From the lowest to the highest value.
+    Ascending,
+
+    /// From the highest to the lowest value.
+    Descending,
+}
+
+/// A [`SortField`] together with the [`Direction`] of ordering by it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SortKey {
+    pub field: SortField,
+    pub direction: Direction,
+}
+
+impl SortKey {
+    /// Creates a new `SortKey` ordering by the field in ascending order.
+    pub fn ascending(field: SortField) -> Self {
+        Self {
+            field,
+            direction: Direction::Ascending,
+        }
+    }
+
+    /// Creates a new `SortKey` ordering by the field in descending order.
+    pub fn descending(field: SortField) -> Self {
+        Self {
+            field,
+            direction: Direction::Descending,
+        }
+    }
+
+    /// Compares two Todo items by this key. Todo items without a value of
+    /// the field, such as without a due date, come last in both directions.
+    pub fn compare(&self, first: &Todo, second: &Todo) -> Ordering {
+        let direction = self.direction;
+
+        match self.field {
+            SortField::Id => compare_values(Some(first.id()), Some(second.id()), direction),
+            SortField::Parent => compare_values(first.parent(), second.parent(), direction),
+            SortField::Status => compare_values(
+                Some(status_rank(&first.status)),
+                Some(status_rank(&second.status)),
+                direction,
+            ),
+            SortField::Priority => {
+                compare_values(Some(first.priority), Some(second.priority), direction)
+            }
+            SortField::Content => compare_values(
+                Some(first.content.to_lowercase()),
+                Some(second.content.to_lowercase()),
+                direction,
+            ),
+            SortField::Due => compare_values(first.due, second.due, direction),
+            SortField::Scheduled => compare_values(first.scheduled, second.scheduled, direction),
+            SortField::Created => compare_values(first.created(), second.created(), direction),
+            SortField::Updated => compare_values(first.updated(), second.updated(), direction),
+            SortField::Completed => {
+                compare_values(first.completed(), second.completed(), direction)
+            }
+        }
+    }
+}
+
+/// Compares two values in the given direction, a missing value being greater
+/// than any other.
+fn compare_values<T: Ord>(first: Option<T>, second: Option<T>, direction: Direction) -> Ordering {
+    match (first, second) {
+        (Some(first), Some(second)) => match direction {
+            Direction::Ascending => first.cmp(&second),
+            Direction::Descending => second.cmp(&first),
+        },
+        (first, second) => second.is_some().cmp(&first.is_some()),
+    }
+}
+
+/// Compares two Todo items by the first of the keys they differ in. Todo
+/// items equal in all keys are equal.
+pub fn compare(keys: &[SortKey], first: &Todo, second: &Todo) -> Ordering {
+    keys.iter()
+        .map(|key| key.compare(first, second))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.direction {
+            Direction::Ascending => write!(f, "{}", self.field.name()),
+            Direction::Descending => write!(f, "-{}", self.field.name()),
+        }
+    }
+}
+
+/// Error returned when a string can't be parsed into a [`SortKey`].
+#[derive(Error, Debug, PartialEq)]
+#[error(
+    "Unknown sort key {0:?}, expected one of {fields}, optionally prefixed with - or +!",
+    fields = field_names()
+)]
+pub struct ParseSortKeyError(String);
+
+/// Returns names of all [`SortField`]s, separated by commas.
+fn field_names() -> String {
+    SortField::ALL.map(SortField::name).join(", ")
+}
+
+impl FromStr for SortKey {
+    type Err = ParseSortKeyError;
+
+    /// Parses a [`SortKey`] from the name of its [`SortField`], such as
+    /// `content`, in any case. The name is prefixed with `-` for descending
+    /// order, and optionally with `+` for ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::sort::{SortField, SortKey};
+    ///
+    /// assert_eq!("-due".parse(), Ok(SortKey::descending(SortField::Due)));
+    /// assert_eq!("Status".parse(), Ok(SortKey::ascending(SortField::Status)));
+    /// assert!("size".parse::<SortKey>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+
+        let (direction, name) = match (text.strip_prefix('-'), text.strip_prefix('+')) {
+            (Some(name), _) => (Direction::Descending, name),
+            (_, Some(name)) => (Direction::Ascending, name),
+            _ => (Direction::Ascending, text),
+        };
+
+        let field = SortField::ALL
+            .into_iter()
+            .find(|field| field.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseSortKeyError(String::from(s)))?;
+
+        Ok(Self { field, direction })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn date(day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2024, 12, day)
+    }
+
+    #[test]
+    fn todos_without_value_come_last_in_both_directions() {
+        let mut early = Todo::new(1, "Lorem");
+        early.due = date(1);
+        let undated = Todo::new(2, "Ipsum");
+        let mut late = Todo::new(3, "Dolor");
+        late.due = date(2);
+
+        let mut todos = vec![&undated, &late, &early];
+
+        todos.sort_by(|a, b| compare(&[SortKey::ascending(SortField::Due)], a, b));
+        assert_eq!(todos, vec![&early, &late, &undated]);
+
+        todos.sort_by(|a, b| compare(&[SortKey::descending(SortField::Due)], a, b));
+        assert_eq!(todos, vec![&late, &early, &undated]);
+    }
+
+    #[test]
+    fn later_keys_break_ties() {
+        let mut first = Todo::new(1, "lorem");
+        first.status = TodoStatus::Custom(String::from("Review"));
+        let second = Todo::new(2, "Lorem");
+        let third = Todo::new(3, "ipsum");
+
+        let keys = [
+            SortKey::ascending(SortField::Content),
+            SortKey::descending(SortField::Status),
+        ];
+
+        let mut todos = vec![&second, &first, &third];
+        todos.sort_by(|a, b| compare(&keys, a, b));
+
+        assert_eq!(todos, vec![&third, &first, &second]);
+    }
+
+    #[test]
+    fn display_round_trips_through_parse() {
+        for field in SortField::ALL {
+            for key in [SortKey::ascending(field), SortKey::descending(field)] {
+                assert_eq!(key.to_string().parse(), Ok(key));
+            }
+        }
+    }
+}
@@ -1,43 +1,205 @@
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
-    io::Write,
-    path::Path,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
 };
 
+use serde::{Deserialize, Serialize};
+
+use crate::journal::Journal;
 use crate::todo::Todo;
+use crate::todo_manager::TodoManager;
+use crate::workspace::{Workspace, DEFAULT_PROJECT};
 
 use super::{TodoStorage, TodoStorageError};
 
 /// A storage implementation that reads and writes todos to a JSON file.
+///
+/// The file holds an object mapping project names to lists of todos, each
+/// stored together with the project's archived and deleted todos and the last
+/// id issued in the project, so that ids of deleted todos are never reused.
+/// Files holding just a list of todos are read as the default project.
+///
+/// Journals of changes of the projects, needed to undo them, are kept in a
+/// separate file next to it, see [`JsonStorage::journal_path`].
 pub struct JsonStorage {}
 
-impl TodoStorage for JsonStorage {
-    /// Load [`Todo`]s from a JSON file at the given path.
+impl JsonStorage {
+    /// Returns path of the file holding [`Journal`]s of the projects stored
+    /// in a JSON file at the given path, e.g. `todos.journal.json` for
+    /// `todos.json`.
     ///
-    /// Returns a [`Vec`] of [`Todo`]s if the file exists and is valid JSON
-    /// matching the expected structure.
-    ///
-    /// Returns an empty [`Vec`] if the file is empty.
+    /// # Examples
     ///
-    /// # Errors
-    ///
-    /// Returns [`Err`] if the file does not exist, is not valid JSON, or if
-    /// the JSON does not match the expected structure.
-    fn load(path: &Path) -> Result<Vec<Todo>, TodoStorageError> {
+    /// ```
+    /// # use std::path::{Path, PathBuf};
+    /// # use doru::storage::JsonStorage;
+    /// assert_eq!(
+    ///     JsonStorage::journal_path(Path::new("/home/me/todos.json")),
+    ///     PathBuf::from("/home/me/todos.journal.json")
+    /// );
+    /// ```
+    pub fn journal_path(path: &Path) -> PathBuf {
+        path.with_extension("journal.json")
+    }
+
+    /// Loads [`Journal`]s of projects from a file at the given path. A missing
+    /// file holds no [`Journal`]s.
+    fn load_journals(path: &Path) -> Result<BTreeMap<String, Journal>, TodoStorageError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(_) => return Err(TodoStorageError::FileError(path.to_path_buf())),
+        };
+
+        serde_json::from_str(&json).map_err(|_| TodoStorageError::ParseError(path.to_path_buf()))
+    }
+
+    /// Saves the non-empty [`Journal`]s of projects of the given [`Workspace`]
+    /// to a file at the given path, removing the file if there are none.
+    fn save_journals(workspace: &Workspace, path: &Path) -> Result<(), TodoStorageError> {
+        let journals: BTreeMap<&str, &Journal> = workspace
+            .project_names()
+            .into_iter()
+            .filter_map(|name| Some((name, workspace.project(name)?.journal())))
+            .filter(|(_, journal)| !journal.is_empty())
+            .collect();
+
+        let result = if journals.is_empty() {
+            fs::remove_file(path).or_else(|e| match e.kind() {
+                ErrorKind::NotFound => Ok(()),
+                _ => Err(e),
+            })
+        } else {
+            let json =
+                serde_json::to_string(&journals).map_err(|_| TodoStorageError::SerializeError)?;
+            fs::write(path, json)
+        };
+
+        result.map_err(|_| TodoStorageError::FileError(path.to_path_buf()))
+    }
+
+    /// Loads projects from a JSON file at the given path, without their
+    /// [`Journal`]s. An empty file holds no projects.
+    fn load_projects(path: &Path) -> Result<BTreeMap<String, TodoManager>, TodoStorageError> {
         let json = fs::read_to_string(path)
             .map_err(|_| TodoStorageError::FileError(path.to_path_buf()))?;
 
         if json.trim().is_empty() {
-            return Ok(vec![]);
+            return Ok(BTreeMap::new());
         }
 
-        let todos: Vec<Todo> = serde_json::from_str(&json)
+        let stored: StoredWorkspace = serde_json::from_str(&json)
             .map_err(|_| TodoStorageError::ParseError(path.to_path_buf()))?;
 
-        Ok(todos)
+        let projects = match stored {
+            StoredWorkspace::Projects { projects } => projects,
+            StoredWorkspace::SingleList(todos) => BTreeMap::from([(
+                String::from(DEFAULT_PROJECT),
+                StoredProject::TodosOnly(todos),
+            )]),
+        };
+
+        Ok(projects
+            .into_iter()
+            .map(|(name, project)| (name, project.into()))
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredWorkspace {
+    Projects {
+        projects: BTreeMap<String, StoredProject>,
+    },
+    SingleList(Vec<Todo>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredProject {
+    WithIdCounter {
+        id_counter: usize,
+        todos: Vec<Todo>,
+        #[serde(default)]
+        archive: Vec<Todo>,
+        #[serde(default)]
+        trash: Vec<Todo>,
+    },
+    TodosOnly(Vec<Todo>),
+}
+
+impl From<StoredProject> for TodoManager {
+    fn from(project: StoredProject) -> Self {
+        match project {
+            StoredProject::WithIdCounter {
+                id_counter,
+                todos,
+                archive,
+                trash,
+            } => TodoManager::new(todos)
+                .with_archive(archive)
+                .with_trash(trash)
+                .with_id_counter(id_counter),
+            StoredProject::TodosOnly(todos) => TodoManager::new(todos),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SavedWorkspace<'a> {
+    projects: BTreeMap<&'a str, SavedProject<'a>>,
+}
+
+#[derive(Serialize)]
+struct SavedProject<'a> {
+    id_counter: usize,
+    todos: Vec<&'a Todo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    archive: Vec<&'a Todo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    trash: Vec<&'a Todo>,
+}
+
+impl<'a> From<&'a TodoManager> for SavedProject<'a> {
+    fn from(manager: &'a TodoManager) -> Self {
+        Self {
+            id_counter: manager.id_counter(),
+            todos: manager.all_todos(),
+            archive: manager.archived_todos(),
+            trash: manager.trashed_todos(),
+        }
     }
+}
 
-    /// Save the given [`Todo`]s to a JSON file at the given path.
+impl TodoStorage for JsonStorage {
+    /// Load [`Todo`]s of the [`DEFAULT_PROJECT`] from a JSON file at the given
+    /// path, see [`load_workspace`](Self::load_workspace). The journal file
+    /// is not read.
+    ///
+    /// Returns an empty [`Vec`] if the file is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the file does not exist, is not valid JSON, or if
+    /// the JSON does not match the expected structure.
+    fn load(path: &Path) -> Result<Vec<Todo>, TodoStorageError> {
+        let projects = Self::load_projects(path)?;
+
+        Ok(projects
+            .get(DEFAULT_PROJECT)
+            .map(TodoManager::all_todos)
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    /// Save the given [`Todo`]s to a JSON file at the given path as a plain
+    /// list, which loads as the [`DEFAULT_PROJECT`], replacing anything the
+    /// file held before.
     ///
     /// # Errors
     ///
@@ -56,15 +218,94 @@ impl TodoStorage for JsonStorage {
 
         Ok(())
     }
+
+    /// Load a [`Workspace`] from a JSON file at the given path.
+    ///
+    /// Returns a [`Workspace`] if the file exists and is valid JSON matching
+    /// the expected structure. A file holding just a list of [`Todo`]s is
+    /// loaded as the [`DEFAULT_PROJECT`].
+    ///
+    /// Returns an empty [`Workspace`] if the file is empty. Projects get their
+    /// [`Journal`]s from the file at [`JsonStorage::journal_path`], if it
+    /// exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the file does not exist, if it or the journal file
+    /// is not valid JSON, or if the JSON does not match the expected
+    /// structure.
+    fn load_workspace(path: &Path) -> Result<Workspace, TodoStorageError> {
+        let projects = Self::load_projects(path)?;
+        let mut journals = Self::load_journals(&Self::journal_path(path))?;
+
+        let projects = projects
+            .into_iter()
+            .map(|(name, manager)| {
+                let journal = journals.remove(&name).unwrap_or_default();
+                (name, manager.with_journal(journal))
+            })
+            .collect();
+
+        Ok(Workspace::new(projects))
+    }
+
+    /// Save the given [`Workspace`] to a JSON file at the given path, and
+    /// [`Journal`]s of its projects to the file at
+    /// [`JsonStorage::journal_path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the files cannot be opened or written to or if the
+    /// [`Todo`]s cannot be serialized.
+    fn save_workspace(workspace: &Workspace, path: &Path) -> Result<(), TodoStorageError> {
+        let projects = workspace
+            .project_names()
+            .into_iter()
+            .filter_map(|name| Some((name, workspace.project(name)?.into())))
+            .collect();
+
+        let json = serde_json::to_string(&SavedWorkspace { projects })
+            .map_err(|_| TodoStorageError::SerializeError)?;
+
+        let mut file = File::options()
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|_| TodoStorageError::FileError(path.to_path_buf()))?;
+
+        write!(file, "{json}").map_err(|_| TodoStorageError::FileError(path.to_path_buf()))?;
+
+        Self::save_journals(workspace, &Self::journal_path(path))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::field::FieldValue;
+    use crate::journal::Operation;
+    use crate::todo::TodoStatus;
     use serde_json::json;
     use std::io::Write;
     use tempfile::{self, NamedTempFile};
 
+    fn default_todos(workspace: &Workspace) -> Vec<Todo> {
+        workspace
+            .project(DEFAULT_PROJECT)
+            .unwrap()
+            .all_todos()
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn workspace_with(todos: Vec<Todo>) -> Workspace {
+        Workspace::new(BTreeMap::from([(
+            String::from(DEFAULT_PROJECT),
+            TodoManager::new(todos),
+        )]))
+    }
+
     #[test]
     fn read_and_parse_valid_file_succeeds() {
         let todos = vec![
@@ -150,4 +391,317 @@ mod test {
             Err(TodoStorageError::FileError(nonexistent_path.to_path_buf()))
         )
     }
+
+    #[test]
+    fn read_ignores_journal_file() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let mut test_file = NamedTempFile::new_in(&test_dir).unwrap();
+        writeln!(test_file, "{}", json!([Todo::new(0, "Lorem")])).unwrap();
+        fs::write(JsonStorage::journal_path(test_file.path()), "Ipsum").unwrap();
+
+        let parsed_todos = JsonStorage::load(test_file.path()).unwrap();
+
+        assert_eq!(parsed_todos, vec![Todo::new(0, "Lorem")]);
+        assert!(JsonStorage::load_workspace(test_file.path()).is_err());
+    }
+
+    #[test]
+    fn read_and_parse_valid_workspace_file_succeeds() {
+        let todos = vec![
+            Todo::new(0, "Lorem"),
+            Todo::new(1, "Ipsum"),
+            Todo::new(2, "Dolor"),
+        ];
+
+        let workspace_json = json!({"projects": {"default": todos, "sit": []}}).to_string();
+
+        let mut test_file = NamedTempFile::new().unwrap();
+        writeln!(test_file, "{workspace_json}").unwrap();
+
+        let workspace = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(workspace.project_names(), vec![DEFAULT_PROJECT, "sit"]);
+        assert_eq!(default_todos(&workspace), todos);
+    }
+
+    #[test]
+    fn read_single_list_file_loads_default_project() {
+        let todos = vec![Todo::new(0, "Lorem"), Todo::new(1, "Ipsum")];
+
+        let todos_json = json!(todos).to_string();
+
+        let mut test_file = NamedTempFile::new().unwrap();
+        writeln!(test_file, "{todos_json}").unwrap();
+
+        let workspace = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(workspace.project_names(), vec![DEFAULT_PROJECT]);
+        assert_eq!(default_todos(&workspace), todos);
+    }
+
+    #[test]
+    fn read_todos_without_optional_fields_succeeds() {
+        let mut test_file = NamedTempFile::new().unwrap();
+        writeln!(
+            test_file,
+            r#"[{{"id":1,"content":"Lorem","status":"Open"}}]"#
+        )
+        .unwrap();
+
+        let workspace = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(default_todos(&workspace), vec![Todo::new(1, "Lorem")]);
+    }
+
+    #[test]
+    fn projects_survive_save_and_load() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let test_file = NamedTempFile::new_in(&test_dir).unwrap();
+
+        let mut workspace = Workspace::default();
+        workspace.project_or_create("lorem").add_todo("Ipsum");
+
+        JsonStorage::save_workspace(&workspace, test_file.path()).unwrap();
+        let parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(parsed.project_names(), vec![DEFAULT_PROJECT, "lorem"]);
+        assert_eq!(
+            parsed.project("lorem").unwrap().all_todos(),
+            workspace.project("lorem").unwrap().all_todos()
+        );
+    }
+
+    #[test]
+    fn ids_of_deleted_todos_are_not_reused_after_load() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let test_file = NamedTempFile::new_in(&test_dir).unwrap();
+
+        let mut workspace = Workspace::default();
+        let manager = workspace.project_or_create(DEFAULT_PROJECT);
+        manager.add_todo("Lorem");
+        let last = manager.add_todo("Ipsum");
+        manager.delete_todo(last).unwrap();
+
+        JsonStorage::save_workspace(&workspace, test_file.path()).unwrap();
+        let mut parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        let id = parsed.project_or_create(DEFAULT_PROJECT).add_todo("Dolor");
+        assert_eq!(id, last + 1);
+    }
+
+    #[test]
+    fn archive_and_trash_survive_save_and_load() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let test_file = NamedTempFile::new_in(&test_dir).unwrap();
+
+        let mut workspace = Workspace::default();
+        let manager = workspace.project_or_create(DEFAULT_PROJECT);
+        let archived = manager.add_todo("Lorem");
+        let trashed = manager.add_todo("Ipsum");
+        manager
+            .change_todo_status(archived, TodoStatus::Done)
+            .unwrap();
+        manager.archive_todo(archived).unwrap();
+        manager.delete_todo(trashed).unwrap();
+
+        JsonStorage::save_workspace(&workspace, test_file.path()).unwrap();
+        let mut parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        let manager = parsed.project_or_create(DEFAULT_PROJECT);
+        assert!(manager.all_todos().is_empty());
+        assert_eq!(manager.archived_todos()[0].id(), archived);
+        assert!(manager.restore_todo(trashed).is_ok());
+    }
+
+    #[test]
+    fn custom_fields_survive_save_and_load() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let mut todo = Todo::new(1, "Lorem");
+        todo.fields = BTreeMap::from([
+            (
+                String::from("ipsum"),
+                FieldValue::String(String::from("42")),
+            ),
+            (String::from("dolor"), FieldValue::Number(42.0)),
+            (String::from("sit"), FieldValue::Bool(false)),
+            (
+                String::from("amet"),
+                FieldValue::Date(chrono::NaiveDate::from_ymd_opt(2024, 12, 24).unwrap()),
+            ),
+        ]);
+
+        JsonStorage::save_workspace(&workspace_with(vec![todo.clone()]), test_file.path()).unwrap();
+        let parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(default_todos(&parsed), vec![todo]);
+    }
+
+    #[test]
+    fn dates_survive_save_and_load() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let mut todo = Todo::new(1, "Lorem");
+        todo.due = chrono::NaiveDate::from_ymd_opt(2024, 12, 24);
+        todo.scheduled = chrono::NaiveDate::from_ymd_opt(2024, 12, 20);
+
+        JsonStorage::save_workspace(&workspace_with(vec![todo.clone()]), test_file.path()).unwrap();
+        let parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(default_todos(&parsed), vec![todo]);
+    }
+
+    #[test]
+    fn multi_line_notes_survive_save_and_load() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let mut todo = Todo::new(1, "Lorem");
+        todo.notes = String::from("Ipsum\n\n  \"Dolor\"\tsit");
+
+        JsonStorage::save_workspace(&workspace_with(vec![todo.clone()]), test_file.path()).unwrap();
+        let parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(default_todos(&parsed), vec![todo]);
+    }
+
+    #[test]
+    fn timestamps_survive_save_and_load() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let mut todo = Todo::new(1, "Lorem");
+        todo.created = Some(chrono::Utc::now());
+        todo.updated = todo.created;
+        todo.completed = todo.created;
+
+        JsonStorage::save_workspace(&workspace_with(vec![todo.clone()]), test_file.path()).unwrap();
+        let parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(default_todos(&parsed), vec![todo]);
+    }
+
+    #[test]
+    fn journals_survive_save_and_load() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let test_file = NamedTempFile::new_in(&test_dir).unwrap();
+        let journal_path = JsonStorage::journal_path(test_file.path());
+
+        let mut workspace = Workspace::default();
+        let id = workspace.project_or_create("lorem").add_todo("Ipsum");
+
+        JsonStorage::save_workspace(&workspace, test_file.path()).unwrap();
+        assert!(journal_path.exists());
+
+        let mut parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+        let manager = parsed.project_mut("lorem").unwrap();
+        assert_eq!(manager.undo(), Ok(Operation::Add(id)));
+        assert!(parsed
+            .project(DEFAULT_PROJECT)
+            .unwrap()
+            .journal()
+            .is_empty());
+
+        parsed.project_mut("lorem").unwrap().redo().unwrap();
+        parsed.project_mut("lorem").unwrap().undo().unwrap();
+        JsonStorage::save_workspace(&parsed, test_file.path()).unwrap();
+
+        let mut parsed = JsonStorage::load_workspace(test_file.path()).unwrap();
+        let manager = parsed.project_mut("lorem").unwrap();
+        assert!(manager.all_todos().is_empty());
+        assert_eq!(manager.redo(), Ok(Operation::Add(id)));
+    }
+
+    #[test]
+    fn saving_without_journals_removes_journal_file() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let test_file = NamedTempFile::new_in(&test_dir).unwrap();
+        let journal_path = JsonStorage::journal_path(test_file.path());
+        fs::write(&journal_path, "{}").unwrap();
+
+        JsonStorage::save_workspace(&Workspace::default(), test_file.path()).unwrap();
+
+        assert!(!journal_path.exists());
+    }
+
+    #[test]
+    fn todos_survive_save_and_load_as_plain_list() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let todos = vec![Todo::new(1, "Lorem"), Todo::new(2, "Ipsum")];
+        let refs: Vec<&Todo> = todos.iter().collect();
+
+        JsonStorage::save(&refs, test_file.path()).unwrap();
+
+        assert_eq!(JsonStorage::load(test_file.path()).unwrap(), todos);
+
+        let workspace = JsonStorage::load_workspace(test_file.path()).unwrap();
+        assert_eq!(default_todos(&workspace), todos);
+    }
+
+    #[test]
+    fn read_empty_file_returns_empty_workspace() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let workspace = JsonStorage::load_workspace(test_file.path()).unwrap();
+
+        assert_eq!(workspace.project_names(), vec![DEFAULT_PROJECT]);
+        assert_eq!(default_todos(&workspace), vec![]);
+    }
+
+    #[test]
+    fn read_nonexistent_workspace_file_fails() {
+        let nonexistent_path = Path::new("/nonexistent/path.json");
+        let result = JsonStorage::load_workspace(nonexistent_path);
+        assert_eq!(
+            result.err(),
+            Some(TodoStorageError::FileError(nonexistent_path.to_path_buf()))
+        )
+    }
+
+    #[test]
+    fn parse_invalid_workspace_json_fails() {
+        let mut test_file = NamedTempFile::new().unwrap();
+        writeln!(test_file, "Lorem ipsum not a todo list json").unwrap();
+
+        let result = JsonStorage::load_workspace(test_file.path());
+
+        assert_eq!(
+            result.err(),
+            Some(TodoStorageError::ParseError(test_file.path().to_path_buf()))
+        );
+    }
+
+    #[test]
+    fn saving_workspace_to_existing_file_succeeds() {
+        let test_file = NamedTempFile::new().unwrap();
+
+        let todos = vec![
+            Todo::new(0, "Lorem"),
+            Todo::new(1, "Ipsum"),
+            Todo::new(2, "Dolor"),
+        ];
+
+        JsonStorage::save_workspace(&workspace_with(todos.clone()), test_file.path()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(test_file.path()).unwrap(),
+            format!(
+                r#"{{"projects":{{"default":{{"id_counter":2,"todos":{}}}}}}}"#,
+                serde_json::to_string(&todos).unwrap()
+            )
+        )
+    }
+
+    #[test]
+    fn saving_workspace_to_nonexistent_file_fails() {
+        let nonexistent_path = Path::new("nonexistent/path.json");
+
+        let workspace = workspace_with(vec![Todo::new(0, "Lorem")]);
+
+        let result = JsonStorage::save_workspace(&workspace, nonexistent_path);
+
+        assert_eq!(
+            result,
+            Err(TodoStorageError::FileError(nonexistent_path.to_path_buf()))
+        )
+    }
 }
@@ -1,11 +1,16 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use thiserror::Error;
 
 use crate::todo::Todo;
+use crate::todo_manager::TodoManager;
+use crate::workspace::{Workspace, DEFAULT_PROJECT};
 
-/// Trait defining the contract for loading and storing [`Todo`]s from/ to
-/// files with arbitrary text format.
+/// Trait defining the contract for loading and storing [`Todo`]s, or a whole
+/// [`Workspace`] of Todo lists, from/ to files with arbitrary text format.
 pub trait TodoStorage {
     /// Loads [`Todo`]s from a file at the given path.
     ///
@@ -24,6 +29,43 @@ pub trait TodoStorage {
     /// reasons, such as the file not existing, not having the necessary
     /// permissions, or the data not being serializable.
     fn save(todos: &[&Todo], path: &Path) -> Result<(), TodoStorageError>;
+
+    /// Loads a [`Workspace`] from a file at the given path.
+    ///
+    /// The default implementation loads the [`Todo`]s with
+    /// [`load`](Self::load) into the [`DEFAULT_PROJECT`] of a new
+    /// [`Workspace`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] in the same cases as [`load`](Self::load).
+    fn load_workspace(path: &Path) -> Result<Workspace, TodoStorageError> {
+        let manager = TodoManager::new(Self::load(path)?);
+
+        Ok(Workspace::new(BTreeMap::from([(
+            String::from(DEFAULT_PROJECT),
+            manager,
+        )])))
+    }
+
+    /// Saves the given [`Workspace`] to a file at the given path.
+    ///
+    /// The default implementation saves only the [`Todo`]s of the
+    /// [`DEFAULT_PROJECT`] with [`save`](Self::save). Storages able to keep
+    /// other projects, archived and deleted [`Todo`]s and id counters should
+    /// override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] in the same cases as [`save`](Self::save).
+    fn save_workspace(workspace: &Workspace, path: &Path) -> Result<(), TodoStorageError> {
+        let todos = workspace
+            .project(DEFAULT_PROJECT)
+            .map(TodoManager::all_todos)
+            .unwrap_or_default();
+
+        Self::save(&todos, path)
+    }
 }
 
 /// Possible errors that can occur while loading or storing [`Todo`] items.
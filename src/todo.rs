@@ -1,7 +1,18 @@
 //! A simple Todo item.
 //!
 //! This module contains the `Todo` type and the `TodoStatus` enum representing
-//! lifecycle of a `Todo`.
+//! lifecycle of a `Todo`, as well as the `Priority` enum ranking `Todo`s by
+//! importance.
+//!
+//! A `Todo` can optionally carry a due date, a scheduled date, a set of
+//! free-form tags, assignees, custom fields holding [`FieldValue`]s,
+//! [`Reference`]s to related resources and a [`Recurrence`] rule. It can be a
+//! subtask of another `Todo` and can depend on other `Todo`s. Longer context
+//! can be attached to a `Todo` as notes, progress as an append-only log of
+//! [`Comment`]s, and small steps as a checklist of [`ChecklistItem`]s. Times of
+//! creation, last modification and completion of a `Todo` are recorded by
+//! the [`TodoManager`](crate::todo_manager::TodoManager), as well as
+//! [`TimeEntry`] intervals of time spent working on it.
 //!
 //! # Examples
 //!
@@ -20,31 +31,269 @@
 //! assert_eq!(todo.status, TodoStatus::InProgress);
 //! ```
 
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    str::FromStr,
+};
+use thiserror::Error;
+
+use crate::field::FieldValue;
+use crate::recurrence::Recurrence;
+use crate::reference::Reference;
 
 /// Status of a Todo item.
-#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize, ValueEnum)]
+///
+/// Besides the built-in statuses, a Todo item can have a custom status defined
+/// by a [`Workflow`](crate::workflow::Workflow). Statuses are stored and parsed
+/// as their names, see [`Display`] and [`FromStr`].
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum TodoStatus {
     Open,
     InProgress,
     Done,
+    Custom(String),
+}
+
+impl Display for TodoStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TodoStatus::Open => f.pad("Open"),
+            TodoStatus::InProgress => f.pad("InProgress"),
+            TodoStatus::Done => f.pad("Done"),
+            TodoStatus::Custom(name) => f.pad(name),
+        }
+    }
+}
+
+/// Error returned when a string can't be parsed into a [`TodoStatus`].
+#[derive(Error, Debug, PartialEq)]
+#[error("Status name can't be empty!")]
+pub struct ParseTodoStatusError;
+
+impl FromStr for TodoStatus {
+    type Err = ParseTodoStatusError;
+
+    /// Parses a [`TodoStatus`] from its name.
+    ///
+    /// Built-in statuses are matched case-insensitively and
+    /// [`InProgress`](TodoStatus::InProgress) can also be spelled
+    /// `in-progress`. Any other non-empty name is a
+    /// [`Custom`](TodoStatus::Custom) status.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::TodoStatus;
+    ///
+    /// assert_eq!("in-progress".parse(), Ok(TodoStatus::InProgress));
+    /// assert_eq!(
+    ///     "Review".parse(),
+    ///     Ok(TodoStatus::Custom(String::from("Review")))
+    /// );
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+
+        match name.to_lowercase().as_str() {
+            "" => Err(ParseTodoStatusError),
+            "open" => Ok(TodoStatus::Open),
+            "inprogress" | "in-progress" => Ok(TodoStatus::InProgress),
+            "done" => Ok(TodoStatus::Done),
+            _ => Ok(TodoStatus::Custom(String::from(name))),
+        }
+    }
+}
+
+impl TryFrom<String> for TodoStatus {
+    type Error = ParseTodoStatusError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TodoStatus> for String {
+    fn from(value: TodoStatus) -> Self {
+        value.to_string()
+    }
+}
+
+/// Priority of a Todo item.
+///
+/// Priorities are ordered from the least important ([`Low`](Priority::Low)) to
+/// the most important ([`Urgent`](Priority::Urgent)).
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default, Serialize, Deserialize, ValueEnum,
+)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+/// An entry of a checklist embedded in a Todo item.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    /// Description of the entry.
+    pub text: String,
+
+    /// Whether the entry is checked off.
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl ChecklistItem {
+    /// Creates a new unchecked `ChecklistItem` with the given text.
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: String::from(text),
+            done: false,
+        }
+    }
+}
+
+/// A timestamped entry of the comment log of a Todo item.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    /// The time the comment was added.
+    pub time: DateTime<Utc>,
+
+    /// Name of the user who added the comment, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Text of the comment.
+    pub text: String,
+}
+
+/// An interval of time spent working on a Todo item.
+///
+/// An entry without an end is a running timer.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TimeEntry {
+    /// The time the timer was started.
+    pub start: DateTime<Utc>,
+
+    /// The time the timer was stopped, if it is not running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// Returns `true` if the timer of this entry is still running.
+    pub fn is_running(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// Returns the length of this entry. A running entry lasts until `now`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeDelta, Utc};
+    /// use doru::todo::TimeEntry;
+    ///
+    /// let now = Utc::now();
+    /// let entry = TimeEntry {
+    ///     start: now - TimeDelta::minutes(90),
+    ///     end: None,
+    /// };
+    ///
+    /// assert_eq!(entry.duration(now), TimeDelta::minutes(90));
+    /// ```
+    pub fn duration(&self, now: DateTime<Utc>) -> TimeDelta {
+        self.end.unwrap_or(now) - self.start
+    }
 }
 
 /// A Todo item.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Todo {
-    id: usize,
+    pub(crate) id: usize,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) parent: Option<usize>,
+
     pub content: String,
     pub status: TodoStatus,
+
+    /// Long-form, possibly multi-line, description of the `Todo`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+
+    /// Importance of the `Todo`.
+    #[serde(default)]
+    pub priority: Priority,
+
+    /// Date by which the `Todo` should be done.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+
+    /// Date on which work on the `Todo` is planned to start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled: Option<NaiveDate>,
+
+    /// Date until which the `Todo` is snoozed, hidden from default listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_until: Option<NaiveDate>,
+
+    /// Free-form labels grouping the `Todo` with others.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+
+    /// Names of the users responsible for the `Todo`.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub assignees: BTreeSet<String>,
+
+    /// Custom fields of the `Todo`, by their keys.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, FieldValue>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) comments: Vec<Comment>,
+
+    /// Links to web pages, local files and other `Todo`s related to the
+    /// `Todo`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Reference>,
+
+    /// Ordered checklist of small steps of the `Todo`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+
+    /// Rule by which the `Todo` repeats once it is done.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(crate) depends_on: BTreeSet<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) created: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) updated: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) completed: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) time_log: Vec<TimeEntry>,
 }
 
 impl Todo {
     /// Creates a new `Todo` with the given id and content.
     ///
     /// The created `Todo` item starts with a default
-    /// [`Open`](TodoStatus::Open) [`TodoStatus`].
+    /// [`Open`](TodoStatus::Open) [`TodoStatus`] and
+    /// [`Normal`](Priority::Normal) [`Priority`].
     ///
     /// # Examples
     ///
@@ -61,8 +310,26 @@ impl Todo {
     pub fn new(id: usize, content: &str) -> Self {
         Self {
             id,
+            parent: None,
             content: String::from(content),
             status: TodoStatus::Open,
+            notes: String::new(),
+            priority: Priority::Normal,
+            due: None,
+            scheduled: None,
+            hidden_until: None,
+            tags: BTreeSet::new(),
+            assignees: BTreeSet::new(),
+            fields: BTreeMap::new(),
+            comments: Vec::new(),
+            references: Vec::new(),
+            checklist: Vec::new(),
+            recurrence: None,
+            depends_on: BTreeSet::new(),
+            created: None,
+            updated: None,
+            completed: None,
+            time_log: Vec::new(),
         }
     }
 
@@ -70,22 +337,285 @@ impl Todo {
     pub fn id(&self) -> usize {
         self.id
     }
+
+    /// Returns id of the `Todo` this `Todo` is a subtask of, if any.
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    /// Returns ids of the `Todo`s that have to be done before this `Todo`.
+    pub fn dependencies(&self) -> &BTreeSet<usize> {
+        &self.depends_on
+    }
+
+    /// Returns the time this `Todo` was created, if known.
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        self.created
+    }
+
+    /// Returns the time this `Todo` was last modified, if known.
+    pub fn updated(&self) -> Option<DateTime<Utc>> {
+        self.updated
+    }
+
+    /// Returns the time this `Todo` was done, if it is
+    /// [`Done`](TodoStatus::Done) and the time is known.
+    pub fn completed(&self) -> Option<DateTime<Utc>> {
+        self.completed
+    }
+
+    /// Returns the comment log of this `Todo`, oldest comment first.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Returns the intervals of time spent working on this `Todo`, oldest
+    /// first.
+    pub fn time_entries(&self) -> &[TimeEntry] {
+        &self.time_log
+    }
+
+    /// Returns the time the timer of this `Todo` was started, if it is
+    /// running.
+    pub fn running_since(&self) -> Option<DateTime<Utc>> {
+        self.time_log
+            .iter()
+            .find(|entry| entry.is_running())
+            .map(|entry| entry.start)
+    }
+
+    /// Returns the total time spent working on this `Todo`, counting a running
+    /// timer until `now`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeDelta, Utc};
+    /// use doru::todo::Todo;
+    ///
+    /// let todo = Todo::new(1, "Learn Rust");
+    ///
+    /// assert_eq!(todo.time_spent(Utc::now()), TimeDelta::zero());
+    /// ```
+    pub fn time_spent(&self, now: DateTime<Utc>) -> TimeDelta {
+        self.time_log.iter().map(|entry| entry.duration(now)).sum()
+    }
+
+    /// Ends the running timer of this `Todo`, if any, at `now`.
+    pub(crate) fn stop_timer(&mut self, now: DateTime<Utc>) {
+        for entry in self.time_log.iter_mut().filter(|entry| entry.is_running()) {
+            entry.end = Some(now);
+        }
+    }
+
+    /// Returns `true` if the `Todo` is [`Done`](TodoStatus::Done).
+    ///
+    /// Whether a custom status counts as done depends on the
+    /// [`Workflow`](crate::workflow::Workflow), see
+    /// [`TodoManager::is_done`](crate::todo_manager::TodoManager::is_done).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::{Todo, TodoStatus};
+    ///
+    /// let mut todo = Todo::new(1, "Learn Rust");
+    /// assert!(!todo.is_done());
+    ///
+    /// todo.status = TodoStatus::Done;
+    /// assert!(todo.is_done());
+    /// ```
+    pub fn is_done(&self) -> bool {
+        self.status == TodoStatus::Done
+    }
+
+    /// Returns `true` if the `Todo` is not done, see
+    /// [`is_done`](Self::is_done), and its due date is before `today`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use doru::todo::{Todo, TodoStatus};
+    ///
+    /// let today = NaiveDate::from_ymd_opt(2024, 12, 24).unwrap();
+    ///
+    /// let mut todo = Todo::new(1, "Buy presents");
+    /// assert!(!todo.is_overdue(today));
+    ///
+    /// todo.due = NaiveDate::from_ymd_opt(2024, 12, 23);
+    /// assert!(todo.is_overdue(today));
+    ///
+    /// todo.status = TodoStatus::Done;
+    /// assert!(!todo.is_overdue(today));
+    /// ```
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.is_done() && self.due.is_some_and(|due| due < today)
+    }
+
+    /// Returns a one-line [`Summary`] of the `Todo` for display, ticked as
+    /// done if `done` is `true`. Formatting the `Todo` itself ticks it by
+    /// [`is_done`](Self::is_done).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::{Todo, TodoStatus};
+    ///
+    /// let mut todo = Todo::new(1, "Learn COBOL");
+    /// todo.status = TodoStatus::Custom(String::from("Won't do"));
+    ///
+    /// assert!(todo.to_string().starts_with("[ ] Learn COBOL"));
+    /// assert!(todo
+    ///     .summary(true)
+    ///     .to_string()
+    ///     .starts_with("[x] Learn COBOL"));
+    /// ```
+    pub fn summary(&self, done: bool) -> Summary<'_> {
+        Summary { todo: self, done }
+    }
+
+    /// Returns `true` if the `Todo` is assigned to the provided user.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::Todo;
+    ///
+    /// let mut todo = Todo::new(1, "Review the PR");
+    /// todo.assignees.insert(String::from("alice"));
+    ///
+    /// assert!(todo.is_assigned_to("alice"));
+    /// assert!(!todo.is_assigned_to("bob"));
+    /// ```
+    pub fn is_assigned_to(&self, user: &str) -> bool {
+        self.assignees.contains(user)
+    }
+
+    /// Returns the number of checked checklist entries of the `Todo` and the
+    /// number of all its checklist entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::{ChecklistItem, Todo};
+    ///
+    /// let mut todo = Todo::new(1, "Pack for the trip");
+    /// todo.checklist.push(ChecklistItem::new("Passport"));
+    /// todo.checklist.push(ChecklistItem::new("Charger"));
+    /// todo.checklist[0].done = true;
+    ///
+    /// assert_eq!(todo.checklist_progress(), (1, 2));
+    /// ```
+    pub fn checklist_progress(&self) -> (usize, usize) {
+        let done = self.checklist.iter().filter(|item| item.done).count();
+        (done, self.checklist.len())
+    }
+
+    /// Returns `true` if the `Todo` is snoozed as of `today`, i.e. it is hidden
+    /// until a date after `today`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use doru::todo::Todo;
+    ///
+    /// let today = NaiveDate::from_ymd_opt(2024, 12, 24).unwrap();
+    ///
+    /// let mut todo = Todo::new(1, "Plan the next year");
+    /// todo.hidden_until = NaiveDate::from_ymd_opt(2024, 12, 27);
+    /// assert!(todo.is_snoozed(today));
+    ///
+    /// todo.hidden_until = Some(today);
+    /// assert!(!todo.is_snoozed(today));
+    /// ```
+    pub fn is_snoozed(&self, today: NaiveDate) -> bool {
+        self.hidden_until.is_some_and(|until| until > today)
+    }
+
+    /// Returns `true` if the `Todo` is labeled with the provided tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::Todo;
+    ///
+    /// let mut todo = Todo::new(1, "Fix the CI pipeline");
+    /// todo.tags.insert(String::from("infra"));
+    ///
+    /// assert!(todo.has_tag("infra"));
+    /// assert!(!todo.has_tag("docs"));
+    /// ```
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
 }
 
 impl Display for Todo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let tick = if self.status == TodoStatus::Done {
-            "x"
-        } else {
-            " "
-        };
+        self.summary(self.is_done()).fmt(f)
+    }
+}
+
+/// One-line summary of a [`Todo`] ticked as done or not, see
+/// [`Todo::summary`].
+pub struct Summary<'a> {
+    todo: &'a Todo,
+    done: bool,
+}
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let todo = self.todo;
+        let tick = if self.done { "x" } else { " " };
 
         // Example format: "[x] Learn Rust [Done] (ID: 42)"
         write!(
             f,
-            "[{}] {:<20} [{:<12?}] (ID: {})",
-            tick, self.content, self.status, self.id
-        )
+            "[{}] {:<20} [{:<12}] (ID: {})",
+            tick, todo.content, todo.status, todo.id
+        )?;
+
+        if todo.priority != Priority::Normal {
+            write!(f, " (Priority: {:?})", todo.priority)?;
+        }
+
+        if let Some(due) = todo.due {
+            write!(f, " (Due: {due})")?;
+        }
+
+        if let Some(scheduled) = todo.scheduled {
+            write!(f, " (Scheduled: {scheduled})")?;
+        }
+
+        if let Some(recurrence) = todo.recurrence {
+            write!(f, " (Repeats: {recurrence})")?;
+        }
+
+        if let Some(until) = todo.hidden_until {
+            write!(f, " (Hidden until: {until})")?;
+        }
+
+        if !todo.depends_on.is_empty() {
+            let ids: Vec<String> = todo.depends_on.iter().map(|id| id.to_string()).collect();
+            write!(f, " (Depends on: {})", ids.join(", "))?;
+        }
+
+        if !todo.checklist.is_empty() {
+            let (done, total) = todo.checklist_progress();
+            write!(f, " ({done}/{total})")?;
+        }
+
+        for user in &todo.assignees {
+            write!(f, " @{user}")?;
+        }
+
+        for tag in &todo.tags {
+            write!(f, " #{tag}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -98,4 +628,132 @@ mod test {
         let todo = Todo::new(42, "Lorem Ipsum");
         assert_eq!(todo.id(), 42);
     }
+
+    #[test]
+    fn todo_due_today_is_not_overdue() {
+        let today = NaiveDate::from_ymd_opt(2024, 12, 24).unwrap();
+
+        let mut todo = Todo::new(1, "Lorem Ipsum");
+        todo.due = Some(today);
+
+        assert!(!todo.is_overdue(today));
+    }
+
+    #[test]
+    fn priorities_are_ordered_by_importance() {
+        assert!(Priority::Low < Priority::Normal);
+        assert!(Priority::Normal < Priority::High);
+        assert!(Priority::High < Priority::Urgent);
+    }
+
+    #[test]
+    fn display_shows_non_default_priority() {
+        let mut todo = Todo::new(1, "Lorem");
+        todo.priority = Priority::High;
+
+        assert_eq!(
+            todo.to_string(),
+            "[ ] Lorem                [Open        ] (ID: 1) (Priority: High)"
+        );
+    }
+
+    #[test]
+    fn display_shows_tags() {
+        let mut todo = Todo::new(1, "Lorem");
+        todo.tags.insert(String::from("infra"));
+        todo.tags.insert(String::from("docs"));
+
+        assert_eq!(
+            todo.to_string(),
+            "[ ] Lorem                [Open        ] (ID: 1) #docs #infra"
+        );
+    }
+
+    #[test]
+    fn time_spent_sums_entries_and_running_timer() {
+        let now = Utc::now();
+
+        let mut todo = Todo::new(1, "Lorem");
+        todo.time_log = vec![
+            TimeEntry {
+                start: now - TimeDelta::hours(3),
+                end: Some(now - TimeDelta::hours(2)),
+            },
+            TimeEntry {
+                start: now - TimeDelta::minutes(30),
+                end: None,
+            },
+        ];
+
+        assert_eq!(todo.time_spent(now), TimeDelta::minutes(90));
+        assert_eq!(todo.running_since(), Some(now - TimeDelta::minutes(30)));
+    }
+
+    #[test]
+    fn display_shows_checklist_progress() {
+        let mut todo = Todo::new(1, "Lorem");
+        todo.checklist = vec![
+            ChecklistItem::new("Ipsum"),
+            ChecklistItem::new("Dolor"),
+            ChecklistItem::new("Sit"),
+        ];
+        todo.checklist[1].done = true;
+
+        assert_eq!(
+            todo.to_string(),
+            "[ ] Lorem                [Open        ] (ID: 1) (1/3)"
+        );
+    }
+
+    #[test]
+    fn parse_status_names() {
+        assert_eq!("Open".parse(), Ok(TodoStatus::Open));
+        assert_eq!("InProgress".parse(), Ok(TodoStatus::InProgress));
+        assert_eq!("DONE".parse(), Ok(TodoStatus::Done));
+        assert_eq!(
+            " Won't do ".parse(),
+            Ok(TodoStatus::Custom(String::from("Won't do")))
+        );
+        assert_eq!("  ".parse::<TodoStatus>(), Err(ParseTodoStatusError));
+    }
+
+    #[test]
+    fn statuses_serialize_as_names() {
+        let statuses = vec![
+            TodoStatus::Open,
+            TodoStatus::InProgress,
+            TodoStatus::Done,
+            TodoStatus::Custom(String::from("Review")),
+        ];
+
+        let json = serde_json::to_string(&statuses).unwrap();
+        assert_eq!(json, r#"["Open","InProgress","Done","Review"]"#);
+
+        let parsed: Vec<TodoStatus> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, statuses);
+    }
+
+    #[test]
+    fn display_shows_custom_status_name() {
+        let mut todo = Todo::new(1, "Lorem");
+        todo.status = TodoStatus::Custom(String::from("Review"));
+
+        assert_eq!(
+            todo.to_string(),
+            "[ ] Lorem                [Review      ] (ID: 1)"
+        );
+    }
+
+    #[test]
+    fn display_shows_dates() {
+        let mut todo = Todo::new(1, "Lorem");
+        todo.due = NaiveDate::from_ymd_opt(2024, 12, 24);
+        todo.scheduled = NaiveDate::from_ymd_opt(2024, 12, 20);
+
+        assert_eq!(
+            todo.to_string(),
+            "[ ] Lorem                [Open        ] (ID: 1) (Due: 2024-12-24) (Scheduled: \
+             2024-12-20)"
+        );
+    }
 }
@@ -44,8 +44,24 @@
 //! # }
 //! ```
 
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+
+use crate::field::FieldValue;
+use crate::journal::{Change, Journal, List, Operation, Step, Value};
+use crate::query::Query;
+use crate::recurrence::Recurrence;
+use crate::reference::Reference;
+use crate::search::{Closeness, SearchHit, SearchQuery};
+use crate::sort::{self, SortKey};
+use crate::todo::ChecklistItem;
+use crate::todo::Comment;
+use crate::todo::Priority;
+use crate::todo::TimeEntry;
 use crate::todo::Todo;
 use crate::todo::TodoStatus;
+use crate::workflow::{TransitionPolicy, Workflow};
 use crate::TodoError;
 
 /// A `TodoManager`, responsible for managing interaction with a collection of
@@ -81,7 +97,7 @@ use crate::TodoError;
 /// manager.edit_todo_content(id, "Learn Rust like a pro");
 /// ```
 ///
-/// Delete the Todo:
+/// Delete the Todo, moving it to the trash:
 /// ```
 /// # let mut manager = doru::todo_manager::TodoManager::default();
 /// # let id = manager.add_todo("Learn Rust");
@@ -91,6 +107,11 @@ use crate::TodoError;
 pub struct TodoManager {
     id_counter: usize,
     todos: Vec<Todo>,
+    archive: Vec<Todo>,
+    trash: Vec<Todo>,
+    workflow: Workflow,
+    transition_policy: TransitionPolicy,
+    journal: Journal,
 }
 
 impl TodoManager {
@@ -108,12 +129,185 @@ impl TodoManager {
     /// assert_eq!(manager.todo_by_id(1).unwrap(), &todos[1]);
     /// ```
     pub fn new(todos: Vec<Todo>) -> Self {
-        let last_id = todos.iter().map(|todo| todo.id()).max().unwrap_or(0);
-
         Self {
-            id_counter: last_id,
+            id_counter: last_id(&todos),
             todos,
+            archive: Vec::new(),
+            trash: Vec::new(),
+            workflow: Workflow::default(),
+            transition_policy: TransitionPolicy::default(),
+            journal: Journal::default(),
+        }
+    }
+
+    /// Makes the `TodoManager` number new [`Todo`]s after the provided last
+    /// issued id, so that ids of deleted [`Todo`]s are never reused. The
+    /// counter never goes below the highest id of the held [`Todo`]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::Todo;
+    /// # use doru::todo_manager::TodoManager;
+    /// let todos = vec![Todo::new(1, "Learn Rust")];
+    /// let mut manager = TodoManager::new(todos).with_id_counter(5);
+    ///
+    /// assert_eq!(manager.add_todo("Learn to cook"), 6);
+    /// assert_eq!(manager.id_counter(), 6);
+    /// ```
+    pub fn with_id_counter(mut self, last_id: usize) -> Self {
+        self.id_counter = self.id_counter.max(last_id);
+        self
+    }
+
+    /// Returns the last id issued by the `TodoManager`. New [`Todo`]s get
+    /// higher ids.
+    pub fn id_counter(&self) -> usize {
+        self.id_counter
+    }
+
+    /// Makes the `TodoManager` hold the provided archived [`Todo`]s, see
+    /// [`archive_todo`](Self::archive_todo).
+    pub fn with_archive(mut self, archived: Vec<Todo>) -> Self {
+        self.archive = archived;
+        self.id_counter = self.id_counter.max(last_id(&self.archive));
+        self
+    }
+
+    /// Makes the `TodoManager` hold the provided deleted [`Todo`]s in its
+    /// trash, see [`delete_todo`](Self::delete_todo).
+    pub fn with_trash(mut self, trashed: Vec<Todo>) -> Self {
+        self.trash = trashed;
+        self.id_counter = self.id_counter.max(last_id(&self.trash));
+        self
+    }
+
+    /// Makes the `TodoManager` accept custom statuses defined by the provided
+    /// [`Workflow`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// # use doru::workflow::{CustomStatus, Workflow};
+    /// let workflow = Workflow::new(vec![CustomStatus::done("Won't do")]);
+    /// let mut manager = TodoManager::default().with_workflow(workflow);
+    ///
+    /// let id = manager.add_todo("Learn COBOL");
+    /// let wont_do: TodoStatus = "won't do".parse().unwrap();
+    /// manager.change_todo_status(id, wont_do).unwrap();
+    ///
+    /// let todo = manager.todo_by_id(id).unwrap();
+    /// assert_eq!(todo.status, TodoStatus::Custom(String::from("Won't do")));
+    /// assert!(manager.is_done(todo));
+    /// ```
+    pub fn with_workflow(mut self, workflow: Workflow) -> Self {
+        self.workflow = workflow;
+        self
+    }
+
+    /// Returns the [`Workflow`] defining custom statuses accepted by the
+    /// `TodoManager`.
+    pub fn workflow(&self) -> &Workflow {
+        &self.workflow
+    }
+
+    /// Returns `true` if the provided [`Todo`] has a status counting as done
+    /// by the [`Workflow`] of the `TodoManager`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::{Todo, TodoStatus};
+    /// # use doru::todo_manager::TodoManager;
+    /// # use doru::workflow::{CustomStatus, Workflow};
+    /// let workflow = Workflow::new(vec![CustomStatus::done("Won't do")]);
+    /// let manager = TodoManager::default().with_workflow(workflow);
+    ///
+    /// let mut todo = Todo::new(1, "Learn COBOL");
+    /// todo.status = TodoStatus::Custom(String::from("Won't do"));
+    /// assert!(manager.is_done(&todo));
+    ///
+    /// // Without the workflow, the custom status doesn't count as done
+    /// assert!(!TodoManager::default().is_done(&todo));
+    /// ```
+    pub fn is_done(&self, todo: &Todo) -> bool {
+        self.workflow.is_done(&todo.status)
+    }
+
+    /// Makes the `TodoManager` allow only status changes permitted by the
+    /// provided [`TransitionPolicy`]. By default, any status can change to any
+    /// other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// # use doru::workflow::TransitionPolicy;
+    /// let policy = TransitionPolicy::default()
+    ///     .allow_only(TodoStatus::Done, vec![TodoStatus::InProgress]);
+    /// let mut manager = TodoManager::default().with_transition_policy(policy);
+    ///
+    /// let id = manager.add_todo("Learn Rust");
+    /// assert!(manager.change_todo_status(id, TodoStatus::Done).is_err());
+    ///
+    /// manager
+    ///     .change_todo_status(id, TodoStatus::InProgress)
+    ///     .unwrap();
+    /// manager.change_todo_status(id, TodoStatus::Done).unwrap();
+    /// ```
+    pub fn with_transition_policy(mut self, policy: TransitionPolicy) -> Self {
+        self.transition_policy = policy;
+        self
+    }
+
+    /// Makes the `TodoManager` continue recording changes to the provided
+    /// [`Journal`], so that changes recorded earlier can be undone.
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    /// Returns the [`Journal`] of changes recorded by the `TodoManager`, see
+    /// [`undo`](Self::undo).
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    /// Runs the provided function on the `TodoManager`, recording all changes
+    /// it makes as a single one, so that they are undone and redone together.
+    /// The change is described by the [`Operation`] of the first of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::journal::Operation;
+    /// # use doru::todo::Priority;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    ///
+    /// let id = manager
+    ///     .grouped(|manager| {
+    ///         let id = manager.add_todo("Learn Rust");
+    ///         manager.set_todo_priority(id, Priority::High)?;
+    ///         manager.add_todo_tag(id, "study").map(|_| id)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(manager.undo(), Ok(Operation::Add(id)));
+    /// assert!(manager.all_todos().is_empty());
+    /// ```
+    pub fn grouped<T>(&mut self, changes: impl FnOnce(&mut Self) -> T) -> T {
+        let started = self.journal.start_group();
+        let output = changes(self);
+
+        if started {
+            self.journal.end_group();
         }
+
+        output
     }
 
     /// Creates a new [`Todo`] with the provided content and stores it
@@ -129,10 +323,44 @@ impl TodoManager {
     /// assert_eq!(manager.todo_by_id(id).unwrap().content, "Learn Rust");
     /// ```
     pub fn add_todo(&mut self, content: &str) -> usize {
-        self.id_counter += 1;
-        self.todos.push(Todo::new(self.id_counter, content));
+        let id = self.create_todo(content);
+        self.record(Operation::Add(id), self.insertion(id));
+
+        id
+    }
+
+    /// Creates a new [`Todo`] with the provided content as a subtask of a
+    /// [`Todo`] with the provided parent id, then returns id of the newly
+    /// created [`Todo`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided parent id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let parent = manager.add_todo("Build a project");
+    /// let id = manager.add_subtask(parent, "Write tests").unwrap();
+    ///
+    /// assert_eq!(manager.todo_by_id(id).unwrap().parent(), Some(parent));
+    ///
+    /// // Try to add a subtask to a non-existent Todo
+    /// let result = manager.add_subtask(42, "This won't work");
+    /// assert!(result.is_err());
+    /// ```
+    pub fn add_subtask(&mut self, parent_id: usize, content: &str) -> Result<usize, TodoError> {
+        if self.todo_by_id(parent_id).is_none() {
+            return Err(TodoError::NotFound(parent_id));
+        }
 
-        self.todos.last().unwrap().id()
+        let id = self.create_todo(content);
+        self.modify_todo(id)?.parent = Some(parent_id);
+        self.record(Operation::Add(id), self.insertion(id));
+
+        Ok(id)
     }
 
     /// Returns a [`Vec`] of references to all internally stored [`Todo`]s
@@ -177,40 +405,99 @@ impl TodoManager {
         self.todos.iter().find(|todo| todo.id() == id)
     }
 
-    /// Returns a [`Vec`] of references to all [`Todo`]s that have the provided
-    /// [`TodoStatus`].
+    /// Returns a reference to the [`Todo`] whose content matches the provided
+    /// text, as a [`SearchQuery`], more closely than the content of any other
+    /// [`Todo`], see [`Closeness`].
+    ///
+    /// A [`Todo`] consisting of exactly the words of the text is preferred,
+    /// then the ones containing all of them, even as starts of longer words.
+    /// Only if there are none, words of the text may be abbreviated or
+    /// mistyped, such as `lrn rst` or `learn ruts` for `Learn Rust`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TodoError::NoMatch`] if no [`Todo`] matches the text, and
+    /// [`TodoError::AmbiguousSelector`] listing the candidates if several
+    /// [`Todo`]s match it equally closely.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use doru::todo::TodoStatus;
     /// # use doru::todo_manager::TodoManager;
+    /// # use doru::TodoError;
     /// let mut manager = TodoManager::default();
-    /// manager.add_todo("Learn Rust");
-    /// manager.add_todo("Learn to cook");
-    /// manager.add_todo("Learn to program");
-    /// manager.add_todo("Learn to dance");
+    /// let deploy = manager.add_todo("Deploy");
+    /// let staging = manager.add_todo("Deploy to staging");
+    /// let prod = manager.add_todo("Deploy to production");
     ///
-    /// manager.change_todo_status(1, TodoStatus::InProgress);
-    /// manager.change_todo_status(3, TodoStatus::Done);
+    /// assert_eq!(manager.find_todo("deploy").unwrap().id(), deploy);
+    /// assert_eq!(manager.find_todo("deploy stag").unwrap().id(), staging);
+    /// assert_eq!(manager.find_todo("dpl prodcution").unwrap().id(), prod);
+    /// assert_eq!(
+    ///     manager.find_todo("deploy to"),
+    ///     Err(TodoError::AmbiguousSelector(
+    ///         String::from("deploy to"),
+    ///         vec![
+    ///             (staging, String::from("Deploy to staging")),
+    ///             (prod, String::from("Deploy to production")),
+    ///         ],
+    ///     ))
+    /// );
+    /// assert!(manager.find_todo("release").is_err());
+    /// ```
+    pub fn find_todo(&self, text: &str) -> Result<&Todo, TodoError> {
+        select(&self.todos, text)
+    }
+
+    /// Returns a reference to the deleted [`Todo`] whose content matches the
+    /// provided text the closest, see [`find_todo`](Self::find_todo).
     ///
-    /// let open_todos = manager.todos_by_status(TodoStatus::Open);
-    /// assert_eq!(open_todos.len(), 2);
+    /// # Errors
     ///
-    /// let in_progress = manager.todos_by_status(TodoStatus::InProgress);
-    /// assert_eq!(in_progress.len(), 1);
+    /// Returns [`Err`] in the same cases as [`find_todo`](Self::find_todo).
+    ///
+    /// # Examples
     ///
-    /// let done = manager.todos_by_status(TodoStatus::Done);
-    /// assert_eq!(done.len(), 1);
     /// ```
-    pub fn todos_by_status(&self, status: TodoStatus) -> Vec<&Todo> {
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    /// manager.delete_todo(id).unwrap();
+    ///
+    /// assert!(manager.find_todo("rust").is_err());
+    /// assert_eq!(manager.find_trashed_todo("rust").unwrap().id(), id);
+    /// ```
+    pub fn find_trashed_todo(&self, text: &str) -> Result<&Todo, TodoError> {
+        select(&self.trash, text)
+    }
+
+    /// Returns a [`Vec`] of references to all direct subtasks of a [`Todo`]
+    /// with the provided id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let parent = manager.add_todo("Build a project");
+    /// let child = manager.add_subtask(parent, "Write tests").unwrap();
+    /// manager.add_subtask(child, "Write unit tests").unwrap();
+    ///
+    /// assert_eq!(manager.children(parent).len(), 1);
+    /// ```
+    pub fn children(&self, id: usize) -> Vec<&Todo> {
         self.todos
             .iter()
-            .filter(|todo| todo.status == status)
+            .filter(|todo| todo.parent() == Some(id))
             .collect()
     }
 
-    /// Changes the content of a [`Todo`] with the provided id.
+    /// Returns progress of a [`Todo`] with the provided id as a number between
+    /// `0.0` and `1.0`.
+    ///
+    /// Progress of a [`Todo`] without subtasks is `1.0` if it is
+    /// [`Done`](TodoStatus::Done) and `0.0` otherwise. Progress of a [`Todo`]
+    /// with subtasks is the average progress of its direct subtasks.
     ///
     /// # Errors
     ///
@@ -219,29 +506,26 @@ impl TodoManager {
     /// # Examples
     ///
     /// ```
+    /// # use doru::todo::TodoStatus;
     /// # use doru::todo_manager::TodoManager;
     /// let mut manager = TodoManager::default();
-    /// let id = manager.add_todo("Learn Rust");
+    /// let parent = manager.add_todo("Build a project");
+    /// let child = manager.add_subtask(parent, "Write code").unwrap();
+    /// manager.add_subtask(parent, "Write tests").unwrap();
     ///
-    /// // Edit content of an existing Todo
-    /// manager.edit_todo_content(id, "Learn Rust like a pro");
+    /// manager.change_todo_status(child, TodoStatus::Done);
     ///
-    /// // Try to edit content of a non-existent Todo
-    /// let result = manager.edit_todo_content(42, "This won't work");
-    /// assert!(result.is_err());
+    /// assert_eq!(manager.todo_progress(parent), Ok(0.5));
     /// ```
-    pub fn edit_todo_content(&mut self, id: usize, content: &str) -> Result<(), TodoError> {
-        let todo = self.todos.iter_mut().find(|todo| todo.id() == id);
-
-        if let Some(todo) = todo {
-            todo.content = String::from(content);
-            Ok(())
-        } else {
-            Err(TodoError::NotFound(id))
-        }
+    pub fn todo_progress(&self, id: usize) -> Result<f64, TodoError> {
+        let todo = self.todo_by_id(id).ok_or(TodoError::NotFound(id))?;
+        Ok(self.progress(todo))
     }
 
-    /// Changes the status of a [`Todo`] with the provided id.
+    /// Returns a [`Vec`] of references to all [`Todo`]s that a [`Todo`] with
+    /// the provided id depends on and that are not done yet, see
+    /// [`is_done`](Self::is_done). Deleted [`Todo`]s in the trash don't block
+    /// others.
     ///
     /// # Errors
     ///
@@ -253,265 +537,3765 @@ impl TodoManager {
     /// # use doru::todo::TodoStatus;
     /// # use doru::todo_manager::TodoManager;
     /// let mut manager = TodoManager::default();
-    /// let id = manager.add_todo("Learn Rust");
+    /// let learn = manager.add_todo("Learn Rust");
+    /// let build = manager.add_todo("Build a project");
     ///
-    /// // Change status of an existing Todo
-    /// manager.change_todo_status(id, TodoStatus::InProgress);
-    /// assert_eq!(
-    ///     manager.todo_by_id(id).unwrap().status,
-    ///     TodoStatus::InProgress
-    /// );
+    /// manager.add_dependency(build, learn).unwrap();
+    /// assert_eq!(manager.blockers(build).unwrap().len(), 1);
     ///
-    /// // Try to change status of a non-existent Todo
-    /// let result = manager.change_todo_status(42, TodoStatus::Done);
-    /// assert!(result.is_err());
+    /// manager.change_todo_status(learn, TodoStatus::Done);
+    /// assert!(manager.blockers(build).unwrap().is_empty());
     /// ```
-    pub fn change_todo_status(&mut self, id: usize, state: TodoStatus) -> Result<(), TodoError> {
-        let todo = self.todos.iter_mut().find(|todo| todo.id() == id);
+    pub fn blockers(&self, id: usize) -> Result<Vec<&Todo>, TodoError> {
+        let todo = self.todo_by_id(id).ok_or(TodoError::NotFound(id))?;
 
-        if let Some(todo) = todo {
-            todo.status = state;
-            Ok(())
-        } else {
-            Err(TodoError::NotFound(id))
-        }
+        Ok(self
+            .todos
+            .iter()
+            .filter(|other| todo.dependencies().contains(&other.id()))
+            .filter(|other| !self.is_done(other))
+            .collect())
     }
 
-    /// Deletes a [`Todo`] with the provided id.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    /// Returns a [`Vec`] of references to all [`Todo`]s that are not
+    /// [`Done`](TodoStatus::Done) and all of whose dependencies are.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use doru::todo::TodoStatus;
     /// # use doru::todo_manager::TodoManager;
     /// let mut manager = TodoManager::default();
-    /// let id = manager.add_todo("Learn Rust");
+    /// let learn = manager.add_todo("Learn Rust");
+    /// let build = manager.add_todo("Build a project");
     ///
-    /// // Delete an existing Todo
-    /// manager.delete_todo(id);
-    /// assert!(manager.todo_by_id(id).is_none());
+    /// manager.add_dependency(build, learn).unwrap();
+    /// assert_eq!(manager.ready_todos().len(), 1);
     ///
-    /// // Try to delete a non-existent Todo
-    /// let result = manager.delete_todo(42);
-    /// assert!(result.is_err());
+    /// manager.change_todo_status(learn, TodoStatus::Done);
+    /// assert_eq!(manager.ready_todos()[0].content, "Build a project");
     /// ```
-    pub fn delete_todo(&mut self, id: usize) -> Result<(), TodoError> {
-        let todo_position = self.todos.iter().position(|todo| todo.id() == id);
-
-        if let Some(position) = todo_position {
-            self.todos.remove(position);
-            Ok(())
-        } else {
-            Err(TodoError::NotFound(id))
-        }
+    pub fn ready_todos(&self) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| !self.is_done(todo))
+            .filter(|todo| self.blockers(todo.id()).is_ok_and(|b| b.is_empty()))
+            .collect()
     }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn new_from_empty_vec_is_empty() {
-        let manager = TodoManager::new(vec![]);
 
-        assert_eq!(manager.todos, vec![]);
+    /// Returns a [`Vec`] of references to all [`Todo`]s created at or after the
+    /// provided time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::{TimeDelta, Utc};
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// manager.add_todo("Learn Rust");
+    ///
+    /// let last_week = Utc::now() - TimeDelta::days(7);
+    /// assert_eq!(manager.todos_created_since(last_week).len(), 1);
+    /// ```
+    pub fn todos_created_since(&self, since: DateTime<Utc>) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.created().is_some_and(|created| created >= since))
+            .collect()
     }
 
-    #[test]
-    fn new_from_empty_has_last_counter_0() {
-        let manager = TodoManager::new(vec![]);
-
-        assert_eq!(manager.id_counter, 0);
+    /// Returns a [`Vec`] of references to all [`Todo`]s last modified at or
+    /// after the provided time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::{TimeDelta, Utc};
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// let since = Utc::now();
+    /// manager.edit_todo_content(id, "Learn Rust like a pro");
+    ///
+    /// assert_eq!(manager.todos_updated_since(since).len(), 1);
+    /// ```
+    pub fn todos_updated_since(&self, since: DateTime<Utc>) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.updated().is_some_and(|updated| updated >= since))
+            .collect()
     }
 
-    #[test]
-    fn new_from_existing_is_filled() {
-        let todos = vec![Todo::new(0, "Lorem"), Todo::new(1, "Ipsum")];
-
-        let manager = TodoManager::new(todos.clone());
-
-        assert_eq!(manager.todos, todos);
+    /// Returns a [`Vec`] of references to all [`Todo`]s completed at or after
+    /// the provided time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::{TimeDelta, Utc};
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    /// manager.add_todo("Learn to cook");
+    ///
+    /// manager.change_todo_status(id, TodoStatus::Done);
+    ///
+    /// let last_week = Utc::now() - TimeDelta::days(7);
+    /// assert_eq!(manager.todos_completed_since(last_week).len(), 1);
+    /// ```
+    pub fn todos_completed_since(&self, since: DateTime<Utc>) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.completed().is_some_and(|completed| completed >= since))
+            .collect()
     }
 
-    #[test]
-    fn new_from_existing_has_correct_id_counter() {
-        let todos = vec![
-            Todo::new(0, "Lorem"),
-            Todo::new(10, "Ipsum"),
-            Todo::new(2, "Dolor"),
-        ];
-
-        let manager = TodoManager::new(todos.clone());
-
-        assert_eq!(manager.id_counter, 10);
-    }
+    /// Returns a [`Vec`] of references to all [`Todo`]s that have the provided
+    /// [`TodoStatus`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// manager.add_todo("Learn Rust");
+    /// manager.add_todo("Learn to cook");
+    /// manager.add_todo("Learn to program");
+    /// manager.add_todo("Learn to dance");
+    ///
+    /// manager.change_todo_status(1, TodoStatus::InProgress);
+    /// manager.change_todo_status(3, TodoStatus::Done);
+    ///
+    /// let open_todos = manager.todos_by_status(TodoStatus::Open);
+    /// assert_eq!(open_todos.len(), 2);
+    ///
+    /// let in_progress = manager.todos_by_status(TodoStatus::InProgress);
+    /// assert_eq!(in_progress.len(), 1);
+    ///
+    /// let done = manager.todos_by_status(TodoStatus::Done);
+    /// assert_eq!(done.len(), 1);
+    /// ```
+    pub fn todos_by_status(&self, status: TodoStatus) -> Vec<&Todo> {
+        let status = self.workflow.resolve(&status).unwrap_or(status);
+
+        self.todos
+            .iter()
+            .filter(|todo| todo.status == status)
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s matching the provided
+    /// [`Query`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::query::Query;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let infra = manager.add_todo("Set up the servers");
+    /// let deploy = manager.add_todo("Deploy the app");
+    /// manager.add_todo("Write the docs");
+    /// manager.add_todo_tag(infra, "infra").unwrap();
+    ///
+    /// let query: Query = "status:open and (tag:infra or content~\"deploy\")"
+    ///     .parse()
+    ///     .unwrap();
+    /// let ids: Vec<usize> =
+    ///     manager.query(&query).iter().map(|todo| todo.id()).collect();
+    ///
+    /// assert_eq!(ids, vec![infra, deploy]);
+    /// ```
+    pub fn query(&self, query: &Query) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| query.matches(todo))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s that have the provided
+    /// [`Priority`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::Priority;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Fix production");
+    /// manager.add_todo("Learn Rust");
+    ///
+    /// manager.set_todo_priority(id, Priority::Urgent);
+    ///
+    /// assert_eq!(manager.todos_with_priority(Priority::Urgent).len(), 1);
+    /// assert_eq!(manager.todos_with_priority(Priority::Normal).len(), 1);
+    /// ```
+    pub fn todos_with_priority(&self, priority: Priority) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.priority == priority)
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s ordered from the most
+    /// to the least important [`Priority`]. [`Todo`]s with equal [`Priority`]
+    /// keep their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::Priority;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// manager.add_todo("Learn Rust");
+    /// let id = manager.add_todo("Fix production");
+    ///
+    /// manager.set_todo_priority(id, Priority::Urgent);
+    ///
+    /// let todos = manager.todos_by_priority();
+    /// assert_eq!(todos[0].content, "Fix production");
+    /// ```
+    pub fn todos_by_priority(&self) -> Vec<&Todo> {
+        let mut todos = self.all_todos();
+        todos.sort_by_key(|todo| std::cmp::Reverse(todo.priority));
+        todos
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s ordered by the
+    /// provided [`SortKey`]s, see [`sort::compare`]. [`Todo`]s equal in all
+    /// the keys keep their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::sort::{SortField, SortKey};
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let rust = manager.add_todo("Learn Rust");
+    /// let cook = manager.add_todo("Learn to cook");
+    /// let dance = manager.add_todo("Learn to dance");
+    /// manager
+    ///     .change_todo_status(rust, TodoStatus::InProgress)
+    ///     .unwrap();
+    ///
+    /// let keys = [
+    ///     SortKey::ascending(SortField::Status),
+    ///     SortKey::descending(SortField::Id),
+    /// ];
+    /// let ids: Vec<usize> = manager
+    ///     .sorted_todos(&keys)
+    ///     .iter()
+    ///     .map(|todo| todo.id())
+    ///     .collect();
+    ///
+    /// assert_eq!(ids, vec![dance, cook, rust]);
+    /// ```
+    pub fn sorted_todos(&self, keys: &[SortKey]) -> Vec<&Todo> {
+        let mut todos = self.all_todos();
+        todos.sort_by(|first, second| sort::compare(keys, first, second));
+        todos
+    }
+
+    /// Returns a [`SearchHit`] for each [`Todo`] whose content or notes
+    /// contain all words and phrases of the provided [`SearchQuery`], ordered
+    /// from the most to the least relevant. Equally relevant [`Todo`]s keep
+    /// their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::search::SearchQuery;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let release = manager.add_todo("Release the app");
+    /// let deploy = manager.add_todo("Deploy to staging");
+    /// manager.add_todo("Write the docs");
+    /// manager
+    ///     .set_todo_notes(release, "Deploy to production")
+    ///     .unwrap();
+    ///
+    /// let query: SearchQuery = "DEPLOY".parse().unwrap();
+    /// let hits = manager.search(&query);
+    ///
+    /// assert_eq!(hits.len(), 2);
+    /// assert_eq!(hits[0].todo.id(), deploy);
+    /// assert_eq!(hits[0].content_matches, vec![0..6]);
+    /// assert_eq!(hits[1].notes_matches, vec![0..6]);
+    /// ```
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchHit<'_>> {
+        let mut hits: Vec<SearchHit> = self
+            .todos
+            .iter()
+            .filter_map(|todo| query.matches(todo))
+            .collect();
+
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+        hits
+    }
+
+    /// Returns all tags used by the stored [`Todo`]s, each with the number of
+    /// [`Todo`]s labeled with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let first = manager.add_todo("Fix the CI pipeline");
+    /// let second = manager.add_todo("Document the CI pipeline");
+    ///
+    /// manager.add_todo_tag(first, "infra");
+    /// manager.add_todo_tag(second, "infra");
+    /// manager.add_todo_tag(second, "docs");
+    ///
+    /// let tags = manager.all_tags();
+    /// assert_eq!(tags["infra"], 2);
+    /// assert_eq!(tags["docs"], 1);
+    /// ```
+    pub fn all_tags(&self) -> BTreeMap<&str, usize> {
+        let mut tags = BTreeMap::new();
+
+        for tag in self.todos.iter().flat_map(|todo| &todo.tags) {
+            *tags.entry(tag.as_str()).or_insert(0) += 1;
+        }
+
+        tags
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s labeled with at least
+    /// one of the provided tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let first = manager.add_todo("Fix the CI pipeline");
+    /// let second = manager.add_todo("Redesign the homepage");
+    /// manager.add_todo("Learn Rust");
+    ///
+    /// manager.add_todo_tag(first, "infra");
+    /// manager.add_todo_tag(second, "frontend");
+    ///
+    /// let todos = manager.todos_with_any_tag(&["infra", "frontend"]);
+    /// assert_eq!(todos.len(), 2);
+    /// ```
+    pub fn todos_with_any_tag(&self, tags: &[&str]) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| tags.iter().any(|tag| todo.has_tag(tag)))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s labeled with every one
+    /// of the provided tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let first = manager.add_todo("Fix the CI pipeline");
+    /// let second = manager.add_todo("Document the CI pipeline");
+    ///
+    /// manager.add_todo_tag(first, "infra");
+    /// manager.add_todo_tag(second, "infra");
+    /// manager.add_todo_tag(second, "docs");
+    ///
+    /// let todos = manager.todos_with_all_tags(&["infra", "docs"]);
+    /// assert_eq!(todos.len(), 1);
+    /// ```
+    pub fn todos_with_all_tags(&self, tags: &[&str]) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| tags.iter().all(|tag| todo.has_tag(tag)))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s assigned to the
+    /// provided user.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Review the PR");
+    /// manager.add_todo("Merge the PR");
+    ///
+    /// manager.assign_todo(id, "alice").unwrap();
+    ///
+    /// assert_eq!(manager.todos_assigned_to("alice")[0].id(), id);
+    /// assert!(manager.todos_assigned_to("bob").is_empty());
+    /// ```
+    pub fn todos_assigned_to(&self, user: &str) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.is_assigned_to(user))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s that have a custom
+    /// field with the provided key, whatever its value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Fix the login page");
+    /// manager.add_todo("Learn Rust");
+    ///
+    /// let ticket = "WEB-42".parse().unwrap();
+    /// manager.set_todo_field(id, "ticket", ticket).unwrap();
+    ///
+    /// assert_eq!(manager.todos_with_field("ticket").len(), 1);
+    /// ```
+    pub fn todos_with_field(&self, key: &str) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.fields.contains_key(key))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s whose custom field
+    /// with the provided key has the provided value, including its type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::field::FieldValue;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let first = manager.add_todo("Fix the login page");
+    /// let second = manager.add_todo("Fix the signup page");
+    ///
+    /// manager
+    ///     .set_todo_field(first, "sprint", FieldValue::Number(7.0))
+    ///     .unwrap();
+    /// manager
+    ///     .set_todo_field(second, "sprint", FieldValue::Number(8.0))
+    ///     .unwrap();
+    ///
+    /// let todos = manager.todos_by_field("sprint", &FieldValue::Number(7.0));
+    /// assert_eq!(todos[0].id(), first);
+    ///
+    /// // A string is not equal to a number
+    /// let text = FieldValue::String(String::from("7"));
+    /// assert!(manager.todos_by_field("sprint", &text).is_empty());
+    /// ```
+    pub fn todos_by_field(&self, key: &str, value: &FieldValue) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.fields.get(key) == Some(value))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s that are not snoozed
+    /// as of `today`, see [`Todo::is_snoozed`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Plan the next year");
+    /// manager.add_todo("Buy presents");
+    ///
+    /// manager.set_todo_hidden_until(id, NaiveDate::from_ymd_opt(2024, 12, 27));
+    ///
+    /// let today = NaiveDate::from_ymd_opt(2024, 12, 24).unwrap();
+    /// assert_eq!(manager.visible_todos(today)[0].content, "Buy presents");
+    /// assert_eq!(manager.snoozed_todos(today)[0].id(), id);
+    /// ```
+    pub fn visible_todos(&self, today: NaiveDate) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| !todo.is_snoozed(today))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s that are snoozed as of
+    /// `today`, see [`Todo::is_snoozed`].
+    pub fn snoozed_todos(&self, today: NaiveDate) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.is_snoozed(today))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s that are overdue as of
+    /// `today`: not done, see [`is_done`](Self::is_done), and due before
+    /// `today`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Buy presents");
+    /// manager.add_todo("Learn Rust");
+    ///
+    /// manager.set_todo_due_date(id, NaiveDate::from_ymd_opt(2024, 12, 23));
+    ///
+    /// let today = NaiveDate::from_ymd_opt(2024, 12, 24).unwrap();
+    /// assert_eq!(manager.overdue_todos(today).len(), 1);
+    /// ```
+    pub fn overdue_todos(&self, today: NaiveDate) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| !self.is_done(todo))
+            .filter(|todo| todo.due.is_some_and(|due| due < today))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s due strictly before
+    /// the provided date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Buy presents");
+    ///
+    /// manager.set_todo_due_date(id, NaiveDate::from_ymd_opt(2024, 12, 23));
+    ///
+    /// let christmas = NaiveDate::from_ymd_opt(2024, 12, 24).unwrap();
+    /// assert_eq!(manager.todos_due_before(christmas).len(), 1);
+    /// ```
+    pub fn todos_due_before(&self, date: NaiveDate) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.due.is_some_and(|due| due < date))
+            .collect()
+    }
+
+    /// Returns a [`Vec`] of references to all [`Todo`]s due between `from` and
+    /// `to`, both dates inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Buy presents");
+    ///
+    /// manager.set_todo_due_date(id, NaiveDate::from_ymd_opt(2024, 12, 23));
+    ///
+    /// let from = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+    /// let to = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+    /// assert_eq!(manager.todos_due_between(from, to).len(), 1);
+    /// ```
+    pub fn todos_due_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<&Todo> {
+        self.todos
+            .iter()
+            .filter(|todo| todo.due.is_some_and(|due| from <= due && due <= to))
+            .collect()
+    }
+
+    /// Changes the content of a [`Todo`] with the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// // Edit content of an existing Todo
+    /// manager.edit_todo_content(id, "Learn Rust like a pro");
+    ///
+    /// // Try to edit content of a non-existent Todo
+    /// let result = manager.edit_todo_content(42, "This won't work");
+    /// assert!(result.is_err());
+    /// ```
+    pub fn edit_todo_content(&mut self, id: usize, content: &str) -> Result<(), TodoError> {
+        self.update(Operation::Edit(id), id, |todo| {
+            todo.content = String::from(content);
+        })
+    }
+
+    /// Replaces the notes of a [`Todo`] with the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// manager.set_todo_notes(id, "Start with the book.");
+    /// assert_eq!(
+    ///     manager.todo_by_id(id).unwrap().notes,
+    ///     "Start with the book."
+    /// );
+    /// ```
+    pub fn set_todo_notes(&mut self, id: usize, notes: &str) -> Result<(), TodoError> {
+        self.update(Operation::Notes(id), id, |todo| {
+            todo.notes = String::from(notes);
+        })
+    }
+
+    /// Appends text to the notes of a [`Todo`] with the provided id, on a new
+    /// line if the notes are not empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// manager.append_todo_notes(id, "Start with the book.");
+    /// manager.append_todo_notes(id, "Then do rustlings.");
+    /// assert_eq!(
+    ///     manager.todo_by_id(id).unwrap().notes,
+    ///     "Start with the book.\nThen do rustlings."
+    /// );
+    /// ```
+    pub fn append_todo_notes(&mut self, id: usize, text: &str) -> Result<(), TodoError> {
+        self.update(Operation::Notes(id), id, |todo| {
+            if !todo.notes.is_empty() {
+                todo.notes.push('\n');
+            }
+
+            todo.notes.push_str(text);
+        })
+    }
+
+    /// Appends a comment to the comment log of a [`Todo`] with the provided
+    /// id, recording the current time and, if known, the comment's author.
+    ///
+    /// Comments can't be edited nor removed, so unlike the notes, they keep
+    /// a history of the progress on the [`Todo`]. See [`Todo::comments`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Migrate the database");
+    ///
+    /// manager
+    ///     .add_comment(id, "Backup taken", Some("alice"))
+    ///     .unwrap();
+    /// manager.add_comment(id, "Schema migrated", None).unwrap();
+    ///
+    /// let comments = manager.todo_by_id(id).unwrap().comments();
+    /// assert_eq!(comments.len(), 2);
+    /// assert_eq!(comments[0].author.as_deref(), Some("alice"));
+    /// assert_eq!(comments[1].text, "Schema migrated");
+    ///
+    /// // Try to comment on a non-existent Todo
+    /// let result = manager.add_comment(42, "This won't work", None);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn add_comment(
+        &mut self,
+        id: usize,
+        text: &str,
+        author: Option<&str>,
+    ) -> Result<(), TodoError> {
+        let todo = self.modify_todo(id)?;
+
+        todo.comments.push(Comment {
+            time: Utc::now(),
+            author: author.map(String::from),
+            text: String::from(text),
+        });
+
+        Ok(())
+    }
+
+    /// Changes the status of a [`Todo`] with the provided id.
+    ///
+    /// The status can be a built-in one or a custom one defined by the
+    /// [`Workflow`] of the `TodoManager`, see
+    /// [`with_workflow`](Self::with_workflow).
+    ///
+    /// When a [`Todo`] with a [`Recurrence`] becomes done, its next occurrence
+    /// is created as a new [`Todo`] and the [`Recurrence`] moves over to
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, if the status is
+    /// not defined, if the [`TransitionPolicy`] of the `TodoManager` doesn't
+    /// allow the change, or if the [`Todo`] is to be done while some of the
+    /// [`Todo`]s it depends on are not, or if the next occurrence of a
+    /// repeating [`Todo`] would be due out of the supported range of dates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// // Change status of an existing Todo
+    /// manager.change_todo_status(id, TodoStatus::InProgress);
+    /// assert_eq!(
+    ///     manager.todo_by_id(id).unwrap().status,
+    ///     TodoStatus::InProgress
+    /// );
+    ///
+    /// // Try to change status of a non-existent Todo
+    /// let result = manager.change_todo_status(42, TodoStatus::Done);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn change_todo_status(&mut self, id: usize, state: TodoStatus) -> Result<(), TodoError> {
+        self.set_status(id, state, false)
+    }
+
+    /// Changes the status of a [`Todo`] with the provided id like
+    /// [`change_todo_status`](Self::change_todo_status), but also allows a
+    /// done [`Todo`] to become not done when the [`TransitionPolicy`] of the
+    /// `TodoManager` requires reopening.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] in the same cases as
+    /// [`change_todo_status`](Self::change_todo_status).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// # use doru::workflow::TransitionPolicy;
+    /// let policy = TransitionPolicy::default().require_reopen();
+    /// let mut manager = TodoManager::default().with_transition_policy(policy);
+    ///
+    /// let id = manager.add_todo("Learn Rust");
+    /// manager.change_todo_status(id, TodoStatus::Done).unwrap();
+    ///
+    /// assert!(manager.change_todo_status(id, TodoStatus::Open).is_err());
+    /// assert!(manager.reopen_todo(id, TodoStatus::Open).is_ok());
+    /// ```
+    pub fn reopen_todo(&mut self, id: usize, state: TodoStatus) -> Result<(), TodoError> {
+        self.set_status(id, state, true)
+    }
+
+    fn set_status(&mut self, id: usize, state: TodoStatus, reopen: bool) -> Result<(), TodoError> {
+        let todo = self.todo_by_id(id).ok_or(TodoError::NotFound(id))?;
+
+        let state = self
+            .workflow
+            .resolve(&state)
+            .ok_or_else(|| TodoError::UnknownStatus(state.to_string()))?;
+
+        let done = self.workflow.is_done(&state);
+
+        let was_done = self.is_done(todo);
+        let reopening = was_done && !done;
+        let blocked_reopen = reopening && self.transition_policy.requires_reopen() && !reopen;
+
+        if blocked_reopen || !self.transition_policy.allows(&todo.status, &state) {
+            return Err(TodoError::InvalidTransition(id, todo.status.clone(), state));
+        }
+
+        if done {
+            let blockers: Vec<usize> = self.blockers(id)?.iter().map(|todo| todo.id()).collect();
+
+            if !blockers.is_empty() {
+                return Err(TodoError::Blocked(id, blockers));
+            }
+        }
+
+        let today = Local::now().date_naive();
+        let base = todo.due.unwrap_or(today);
+
+        let next_due = match todo.recurrence {
+            Some(recurrence) if done && !was_done => Some(
+                recurrence
+                    .next_due(todo.due, today)
+                    .ok_or(TodoError::RecurrenceOutOfRange(id))?,
+            ),
+            _ => None,
+        };
+
+        let (recurrence, mut steps) = self.track(id, |todo| {
+            if !done {
+                todo.completed = None;
+            } else if !was_done {
+                todo.completed = todo.updated;
+            }
+
+            todo.status = state;
+
+            if done {
+                todo.stop_timer(Utc::now());
+            }
+
+            next_due.and_then(|_| todo.recurrence.take())
+        })?;
+
+        if let (Some(recurrence), Some(due)) = (recurrence, next_due) {
+            let finished = self
+                .todo_by_id(id)
+                .cloned()
+                .ok_or(TodoError::NotFound(id))?;
+            let next = self.add_next_occurrence(&finished, recurrence.anchored(base), due);
+            steps.extend(self.insertion(next));
+        }
+
+        self.record(Operation::Status(id), steps);
+
+        Ok(())
+    }
+
+    /// Starts the timer of a [`Todo`] with the provided id, logging time spent
+    /// working on it. A timer of another [`Todo`] that is running is stopped
+    /// first, so at most one timer runs at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, or if its timer
+    /// is already running.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let learn = manager.add_todo("Learn Rust");
+    /// let build = manager.add_todo("Build a project");
+    ///
+    /// manager.start_timer(learn).unwrap();
+    /// manager.start_timer(build).unwrap();
+    ///
+    /// assert_eq!(manager.running_timer().unwrap().id(), build);
+    /// assert_eq!(manager.todo_by_id(learn).unwrap().time_entries().len(), 1);
+    /// ```
+    pub fn start_timer(&mut self, id: usize) -> Result<(), TodoError> {
+        if self
+            .todo_by_id(id)
+            .ok_or(TodoError::NotFound(id))?
+            .running_since()
+            .is_some()
+        {
+            return Err(TodoError::TimerRunning(id));
+        }
+
+        let now = Utc::now();
+
+        if let Some(running) = self.running_timer().map(|todo| todo.id()) {
+            self.modify_todo(running)?.stop_timer(now);
+        }
+
+        self.modify_todo(id)?.time_log.push(TimeEntry {
+            start: now,
+            end: None,
+        });
+
+        Ok(())
+    }
+
+    /// Starts working on a [`Todo`] with the provided id: changes its status
+    /// to [`InProgress`](TodoStatus::InProgress) and starts its timer, see
+    /// [`start_timer`](Self::start_timer). Nothing is changed if either step
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the timer of the [`Todo`] is already running, or in
+    /// the same cases as [`change_todo_status`](Self::change_todo_status).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// # use doru::TodoError;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// manager.start_todo(id).unwrap();
+    /// manager.change_todo_status(id, TodoStatus::Open).unwrap();
+    ///
+    /// assert_eq!(manager.start_todo(id), Err(TodoError::TimerRunning(id)));
+    /// assert_eq!(manager.todo_by_id(id).unwrap().status, TodoStatus::Open);
+    /// ```
+    pub fn start_todo(&mut self, id: usize) -> Result<(), TodoError> {
+        if self
+            .todo_by_id(id)
+            .ok_or(TodoError::NotFound(id))?
+            .running_since()
+            .is_some()
+        {
+            return Err(TodoError::TimerRunning(id));
+        }
+
+        self.change_todo_status(id, TodoStatus::InProgress)?;
+        self.start_timer(id)
+    }
+
+    /// Stops the running timer and returns id of the [`Todo`] it belongs to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no timer is running.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// manager.start_timer(id).unwrap();
+    /// assert_eq!(manager.stop_timer(), Ok(id));
+    ///
+    /// // Nothing to stop anymore
+    /// assert!(manager.stop_timer().is_err());
+    /// ```
+    pub fn stop_timer(&mut self) -> Result<usize, TodoError> {
+        let id = self
+            .running_timer()
+            .map(|todo| todo.id())
+            .ok_or(TodoError::NoTimerRunning)?;
+
+        self.modify_todo(id)?.stop_timer(Utc::now());
+
+        Ok(id)
+    }
+
+    /// Returns a reference to the [`Todo`] whose timer is running, if any.
+    pub fn running_timer(&self) -> Option<&Todo> {
+        self.todos
+            .iter()
+            .find(|todo| todo.running_since().is_some())
+    }
+
+    /// Returns the total time spent working on a [`Todo`] with the provided
+    /// id, counting its running timer until now.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::TimeDelta;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// assert_eq!(manager.time_spent(id), Ok(TimeDelta::zero()));
+    /// assert!(manager.time_spent(42).is_err());
+    /// ```
+    pub fn time_spent(&self, id: usize) -> Result<TimeDelta, TodoError> {
+        self.todo_by_id(id)
+            .map(|todo| todo.time_spent(Utc::now()))
+            .ok_or(TodoError::NotFound(id))
+    }
+
+    /// Moves a [`Todo`] with the provided id, together with all its subtasks,
+    /// under a new parent [`Todo`]. Passing [`None`] as the parent makes the
+    /// [`Todo`] a top-level one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if either of the [`Todo`]s doesn't exist, or if the
+    /// new parent is the moved [`Todo`] itself or one of its subtasks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let first = manager.add_todo("Build a project");
+    /// let second = manager.add_todo("Publish a project");
+    /// let child = manager.add_subtask(first, "Write a README").unwrap();
+    ///
+    /// manager.move_subtree(child, Some(second)).unwrap();
+    /// assert_eq!(manager.todo_by_id(child).unwrap().parent(), Some(second));
+    ///
+    /// // A Todo can't be moved under its own subtask
+    /// let result = manager.move_subtree(second, Some(child));
+    /// assert!(result.is_err());
+    /// ```
+    pub fn move_subtree(&mut self, id: usize, parent: Option<usize>) -> Result<(), TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        if let Some(parent_id) = parent {
+            if self.todo_by_id(parent_id).is_none() {
+                return Err(TodoError::NotFound(parent_id));
+            }
+
+            if self.subtree_ids(id).contains(&parent_id) {
+                return Err(TodoError::InvalidParent(id, parent_id));
+            }
+        }
+
+        self.update(Operation::Move(id), id, |todo| todo.parent = parent)
+    }
+
+    /// Makes a [`Todo`] with the provided id depend on a [`Todo`] with the
+    /// provided dependency id, so that it can't be [`Done`](TodoStatus::Done)
+    /// before the dependency is.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if either of the [`Todo`]s doesn't exist, or if the
+    /// dependency would create a cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let learn = manager.add_todo("Learn Rust");
+    /// let build = manager.add_todo("Build a project");
+    ///
+    /// manager.add_dependency(build, learn).unwrap();
+    ///
+    /// // Build can't be done before learn is
+    /// assert!(manager.change_todo_status(build, TodoStatus::Done).is_err());
+    ///
+    /// // Dependencies can't form cycles
+    /// assert!(manager.add_dependency(learn, build).is_err());
+    /// ```
+    pub fn add_dependency(&mut self, id: usize, dependency_id: usize) -> Result<(), TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        if self.todo_by_id(dependency_id).is_none() {
+            return Err(TodoError::MissingDependency(dependency_id));
+        }
+
+        if self.depends_transitively(dependency_id, id) {
+            return Err(TodoError::DependencyCycle(id, dependency_id));
+        }
+
+        self.update(Operation::Depend(id), id, |todo| {
+            todo.depends_on.insert(dependency_id);
+        })
+    }
+
+    /// Removes a dependency of a [`Todo`] with the provided id on a [`Todo`]
+    /// with the provided dependency id. Removing a dependency that doesn't
+    /// exist does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let learn = manager.add_todo("Learn Rust");
+    /// let build = manager.add_todo("Build a project");
+    ///
+    /// manager.add_dependency(build, learn).unwrap();
+    /// manager.remove_dependency(build, learn).unwrap();
+    ///
+    /// assert!(manager.todo_by_id(build).unwrap().dependencies().is_empty());
+    /// ```
+    pub fn remove_dependency(&mut self, id: usize, dependency_id: usize) -> Result<(), TodoError> {
+        self.update(Operation::Depend(id), id, |todo| {
+            todo.depends_on.remove(&dependency_id);
+        })
+    }
+
+    /// Sets or clears the [`Recurrence`] of a [`Todo`] with the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::recurrence::Recurrence;
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Update dependencies");
+    ///
+    /// manager.set_todo_recurrence(id, Some(Recurrence::Weekly));
+    ///
+    /// // Finishing a repeating Todo creates its next occurrence
+    /// manager.change_todo_status(id, TodoStatus::Done);
+    /// assert_eq!(manager.todos_by_status(TodoStatus::Open).len(), 1);
+    /// ```
+    pub fn set_todo_recurrence(
+        &mut self,
+        id: usize,
+        recurrence: Option<Recurrence>,
+    ) -> Result<(), TodoError> {
+        self.update(Operation::Repeat(id), id, |todo| {
+            todo.recurrence = recurrence
+        })
+    }
+
+    /// Changes the [`Priority`] of a [`Todo`] with the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::Priority;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Fix production");
+    ///
+    /// manager.set_todo_priority(id, Priority::Urgent);
+    /// assert_eq!(manager.todo_by_id(id).unwrap().priority, Priority::Urgent);
+    ///
+    /// // Try to change priority of a non-existent Todo
+    /// let result = manager.set_todo_priority(42, Priority::Low);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn set_todo_priority(&mut self, id: usize, priority: Priority) -> Result<(), TodoError> {
+        self.update(Operation::Priority(id), id, |todo| todo.priority = priority)
+    }
+
+    /// Labels a [`Todo`] with the provided id with a tag. Adding a tag the
+    /// [`Todo`] already has does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Fix the CI pipeline");
+    ///
+    /// manager.add_todo_tag(id, "infra");
+    /// assert!(manager.todo_by_id(id).unwrap().has_tag("infra"));
+    /// ```
+    pub fn add_todo_tag(&mut self, id: usize, tag: &str) -> Result<(), TodoError> {
+        self.update(Operation::Tag(id), id, |todo| {
+            todo.tags.insert(String::from(tag));
+        })
+    }
+
+    /// Removes a tag from a [`Todo`] with the provided id. Removing a tag the
+    /// [`Todo`] doesn't have does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Fix the CI pipeline");
+    ///
+    /// manager.add_todo_tag(id, "infra");
+    /// manager.remove_todo_tag(id, "infra");
+    /// assert!(!manager.todo_by_id(id).unwrap().has_tag("infra"));
+    /// ```
+    pub fn remove_todo_tag(&mut self, id: usize, tag: &str) -> Result<(), TodoError> {
+        self.update(Operation::Tag(id), id, |todo| {
+            todo.tags.remove(tag);
+        })
+    }
+
+    /// Assigns a [`Todo`] with the provided id to the provided user, in
+    /// addition to its other assignees.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    pub fn assign_todo(&mut self, id: usize, user: &str) -> Result<(), TodoError> {
+        self.update(Operation::Assign(id), id, |todo| {
+            todo.assignees.insert(String::from(user));
+        })
+    }
+
+    /// Removes the provided user from the assignees of a [`Todo`] with the
+    /// provided id. Removing a user the [`Todo`] isn't assigned to does
+    /// nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Review the PR");
+    ///
+    /// manager.assign_todo(id, "alice").unwrap();
+    /// manager.assign_todo(id, "bob").unwrap();
+    /// manager.unassign_todo(id, "alice").unwrap();
+    ///
+    /// let todo = manager.todo_by_id(id).unwrap();
+    /// assert!(!todo.is_assigned_to("alice"));
+    /// assert!(todo.is_assigned_to("bob"));
+    /// ```
+    pub fn unassign_todo(&mut self, id: usize, user: &str) -> Result<(), TodoError> {
+        self.update(Operation::Assign(id), id, |todo| {
+            todo.assignees.remove(user);
+        })
+    }
+
+    /// Adds a [`Reference`] to a [`Todo`] with the provided id. Adding a
+    /// reference the [`Todo`] already has does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, or if the
+    /// reference points to a [`Todo`] that doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::reference::Reference;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Fix the login page");
+    ///
+    /// let issue: Reference = "https://example.com/issues/42".parse().unwrap();
+    /// manager.add_todo_reference(id, issue.clone()).unwrap();
+    /// assert_eq!(manager.todo_by_id(id).unwrap().references, vec![issue]);
+    ///
+    /// // Try to reference a non-existent Todo
+    /// let result = manager.add_todo_reference(id, Reference::Todo(42));
+    /// assert!(result.is_err());
+    /// ```
+    pub fn add_todo_reference(&mut self, id: usize, reference: Reference) -> Result<(), TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        if let Reference::Todo(other) = reference {
+            if self.todo_by_id(other).is_none() {
+                return Err(TodoError::NotFound(other));
+            }
+        }
+
+        self.update(Operation::Link(id), id, |todo| {
+            if !todo.references.contains(&reference) {
+                todo.references.push(reference);
+            }
+        })
+    }
+
+    /// Removes a [`Reference`] from a [`Todo`] with the provided id. Removing
+    /// a reference the [`Todo`] doesn't have does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    pub fn remove_todo_reference(
+        &mut self,
+        id: usize,
+        reference: &Reference,
+    ) -> Result<(), TodoError> {
+        self.update(Operation::Link(id), id, |todo| {
+            todo.references.retain(|other| other != reference)
+        })
+    }
+
+    /// Sets the custom field with the provided key of a [`Todo`] with the
+    /// provided id, replacing its previous value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::field::FieldValue;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Call the customer");
+    ///
+    /// let customer = FieldValue::String(String::from("ACME"));
+    /// manager
+    ///     .set_todo_field(id, "customer", customer.clone())
+    ///     .unwrap();
+    ///
+    /// let todo = manager.todo_by_id(id).unwrap();
+    /// assert_eq!(todo.fields.get("customer"), Some(&customer));
+    /// ```
+    pub fn set_todo_field(
+        &mut self,
+        id: usize,
+        key: &str,
+        value: FieldValue,
+    ) -> Result<(), TodoError> {
+        self.update(Operation::Field(id), id, |todo| {
+            todo.fields.insert(String::from(key), value);
+        })
+    }
+
+    /// Removes the custom field with the provided key from a [`Todo`] with the
+    /// provided id. Removing a field the [`Todo`] doesn't have does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    pub fn unset_todo_field(&mut self, id: usize, key: &str) -> Result<(), TodoError> {
+        self.update(Operation::Field(id), id, |todo| {
+            todo.fields.remove(key);
+        })
+    }
+
+    /// Appends an unchecked entry with the provided text to the checklist of a
+    /// [`Todo`] with the provided id, then returns index of the new entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Pack for the trip");
+    ///
+    /// manager.add_checklist_item(id, "Passport").unwrap();
+    /// let index = manager.add_checklist_item(id, "Charger").unwrap();
+    ///
+    /// assert_eq!(index, 1);
+    /// assert_eq!(
+    ///     manager.todo_by_id(id).unwrap().checklist[index].text,
+    ///     "Charger"
+    /// );
+    /// ```
+    pub fn add_checklist_item(&mut self, id: usize, text: &str) -> Result<usize, TodoError> {
+        self.update(Operation::Checklist(id), id, |todo| {
+            todo.checklist.push(ChecklistItem::new(text));
+            todo.checklist.len() - 1
+        })
+    }
+
+    /// Checks off the checklist entry at the provided index of a [`Todo`] with
+    /// the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, or if it has no
+    /// checklist entry at the provided index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Pack for the trip");
+    /// let index = manager.add_checklist_item(id, "Passport").unwrap();
+    ///
+    /// manager.check_checklist_item(id, index).unwrap();
+    /// assert!(manager.todo_by_id(id).unwrap().checklist[index].done);
+    ///
+    /// // Try to check a non-existent entry
+    /// let result = manager.check_checklist_item(id, 42);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn check_checklist_item(&mut self, id: usize, index: usize) -> Result<(), TodoError> {
+        self.check_checklist_index(id, index)?;
+        self.update(Operation::Checklist(id), id, |todo| {
+            todo.checklist[index].done = true;
+        })
+    }
+
+    /// Unchecks the checklist entry at the provided index of a [`Todo`] with
+    /// the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, or if it has no
+    /// checklist entry at the provided index.
+    pub fn uncheck_checklist_item(&mut self, id: usize, index: usize) -> Result<(), TodoError> {
+        self.check_checklist_index(id, index)?;
+        self.update(Operation::Checklist(id), id, |todo| {
+            todo.checklist[index].done = false;
+        })
+    }
+
+    /// Removes the checklist entry at the provided index of a [`Todo`] with
+    /// the provided id, shifting the following entries up.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, or if it has no
+    /// checklist entry at the provided index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Pack for the trip");
+    /// manager.add_checklist_item(id, "Passport").unwrap();
+    /// manager.add_checklist_item(id, "Charger").unwrap();
+    ///
+    /// manager.remove_checklist_item(id, 0).unwrap();
+    /// assert_eq!(manager.todo_by_id(id).unwrap().checklist[0].text, "Charger");
+    /// ```
+    pub fn remove_checklist_item(&mut self, id: usize, index: usize) -> Result<(), TodoError> {
+        self.check_checklist_index(id, index)?;
+        self.update(Operation::Checklist(id), id, |todo| {
+            todo.checklist.remove(index);
+        })
+    }
+
+    /// Sets or clears the due date of a [`Todo`] with the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Buy presents");
+    ///
+    /// let christmas = NaiveDate::from_ymd_opt(2024, 12, 24);
+    /// manager.set_todo_due_date(id, christmas);
+    /// assert_eq!(manager.todo_by_id(id).unwrap().due, christmas);
+    ///
+    /// // Clear the due date
+    /// manager.set_todo_due_date(id, None);
+    /// assert_eq!(manager.todo_by_id(id).unwrap().due, None);
+    /// ```
+    pub fn set_todo_due_date(
+        &mut self,
+        id: usize,
+        due: Option<NaiveDate>,
+    ) -> Result<(), TodoError> {
+        self.update(Operation::Due(id), id, |todo| todo.due = due)
+    }
+
+    /// Sets or clears the scheduled date of a [`Todo`] with the provided id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::NaiveDate;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Buy presents");
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2024, 12, 20);
+    /// manager.set_todo_scheduled_date(id, date);
+    /// assert_eq!(manager.todo_by_id(id).unwrap().scheduled, date);
+    /// ```
+    pub fn set_todo_scheduled_date(
+        &mut self,
+        id: usize,
+        scheduled: Option<NaiveDate>,
+    ) -> Result<(), TodoError> {
+        self.update(Operation::Schedule(id), id, |todo| {
+            todo.scheduled = scheduled
+        })
+    }
+
+    /// Snoozes a [`Todo`] with the provided id, hiding it from
+    /// [`visible_todos`](Self::visible_todos) until the provided date, or
+    /// wakes it up if [`None`] is provided.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    pub fn set_todo_hidden_until(
+        &mut self,
+        id: usize,
+        until: Option<NaiveDate>,
+    ) -> Result<(), TodoError> {
+        self.update(Operation::Snooze(id), id, |todo| todo.hidden_until = until)
+    }
+
+    /// Deletes a [`Todo`] with the provided id, moving it to the trash, from
+    /// where it can be restored with [`restore_todo`](Self::restore_todo).
+    ///
+    /// Dependencies and references of other [`Todo`]s on the deleted one are
+    /// kept until the trash is emptied, but a [`Todo`] in the trash doesn't
+    /// block the ones depending on it, see [`blockers`](Self::blockers).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists or if the
+    /// [`Todo`] has subtasks, see
+    /// [`delete_todo_cascade`](Self::delete_todo_cascade).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// // Delete an existing Todo
+    /// manager.delete_todo(id);
+    /// assert!(manager.todo_by_id(id).is_none());
+    /// assert_eq!(manager.trashed_todos()[0].id(), id);
+    ///
+    /// // Try to delete a non-existent Todo
+    /// let result = manager.delete_todo(42);
+    /// assert!(result.is_err());
+    /// ```
+    pub fn delete_todo(&mut self, id: usize) -> Result<(), TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        if !self.children(id).is_empty() {
+            return Err(TodoError::HasSubtasks(id));
+        }
+
+        let steps = self.move_to_trash(id);
+        self.record(Operation::Delete(id), steps);
+
+        Ok(())
+    }
+
+    /// Deletes a [`Todo`] with the provided id together with all its subtasks,
+    /// moving them to the trash.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let parent = manager.add_todo("Build a project");
+    /// let child = manager.add_subtask(parent, "Write tests").unwrap();
+    ///
+    /// // A Todo with subtasks can't be deleted on its own
+    /// assert!(manager.delete_todo(parent).is_err());
+    ///
+    /// manager.delete_todo_cascade(parent);
+    /// assert!(manager.todo_by_id(parent).is_none());
+    /// assert!(manager.todo_by_id(child).is_none());
+    /// ```
+    pub fn delete_todo_cascade(&mut self, id: usize) -> Result<(), TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        let steps = self.move_to_trash(id);
+        self.record(Operation::Delete(id), steps);
+
+        Ok(())
+    }
+
+    /// Returns a [`Vec`] of references to all deleted [`Todo`]s in the trash.
+    pub fn trashed_todos(&self) -> Vec<&Todo> {
+        self.trash.iter().collect()
+    }
+
+    /// Restores a deleted [`Todo`] with the provided id, together with its
+    /// subtasks deleted with it, from the trash.
+    ///
+    /// Restored [`Todo`]s are put back to their place by id, which is the
+    /// order the [`Todo`]s were added in, without reordering the others. A
+    /// restored [`Todo`] whose parent no longer exists becomes a top-level
+    /// one. Dependencies and references of other [`Todo`]s on the restored
+    /// ones were kept, so they apply again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id is in the trash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// manager.delete_todo(id).unwrap();
+    /// manager.restore_todo(id).unwrap();
+    ///
+    /// assert_eq!(manager.todo_by_id(id).unwrap().content, "Learn Rust");
+    /// assert!(manager.trashed_todos().is_empty());
+    /// ```
+    pub fn restore_todo(&mut self, id: usize) -> Result<(), TodoError> {
+        let restored = self
+            .trash
+            .iter()
+            .find(|todo| todo.id() == id)
+            .ok_or(TodoError::NotFound(id))?;
+
+        let orphaned = restored
+            .parent
+            .is_some_and(|parent| self.todo_by_id(parent).is_none());
+
+        let ids = subtree_in(&self.trash, id);
+        let mut steps = self.move_todos(&ids, List::Trash, List::Todos);
+
+        if orphaned {
+            let ((), orphan) = self.track(id, |todo| todo.parent = None)?;
+            steps.extend(orphan);
+        }
+
+        let now = Utc::now();
+
+        for todo in self
+            .todos
+            .iter_mut()
+            .filter(|todo| ids.contains(&todo.id()))
+        {
+            todo.updated = Some(now);
+        }
+
+        self.record(Operation::Restore(id), steps);
+
+        Ok(())
+    }
+
+    /// Removes all [`Todo`]s from the trash, together with dependencies and
+    /// references of other [`Todo`]s on them, then returns how many were
+    /// removed.
+    ///
+    /// The removal is recorded in the [`Journal`] together with the removed
+    /// [`Todo`]s, so it can be undone as long as the change is kept, see
+    /// [`JOURNAL_LIMIT`](crate::journal::JOURNAL_LIMIT).
+    pub fn empty_trash(&mut self) -> usize {
+        let ids: BTreeSet<usize> = self.trash.iter().map(|todo| todo.id()).collect();
+
+        let mut steps = self.forget_dependencies(&ids);
+        steps.extend(self.forget_references(&ids));
+
+        steps.extend(
+            std::mem::take(&mut self.trash)
+                .into_iter()
+                .map(|todo| Step::Remove {
+                    list: List::Trash,
+                    index: 0,
+                    todo,
+                }),
+        );
+
+        self.record(Operation::EmptyTrash, steps);
+
+        ids.len()
+    }
+
+    /// Archives a done [`Todo`] with the provided id together with all its
+    /// subtasks. Archived [`Todo`]s are kept, but are no longer among
+    /// [`all_todos`](Self::all_todos) nor any other listed [`Todo`]s.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no [`Todo`] with provided id exists, or if it or any
+    /// of its subtasks is not done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// // Only done Todos can be archived
+    /// assert!(manager.archive_todo(id).is_err());
+    ///
+    /// manager.change_todo_status(id, TodoStatus::Done).unwrap();
+    /// manager.archive_todo(id).unwrap();
+    ///
+    /// assert!(manager.all_todos().is_empty());
+    /// assert_eq!(manager.archived_todos()[0].id(), id);
+    /// ```
+    pub fn archive_todo(&mut self, id: usize) -> Result<(), TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        if !self.is_subtree_done(id) {
+            return Err(TodoError::NotDone(id));
+        }
+
+        let ids = self.subtree_ids(id);
+        let steps = self.move_todos(&ids, List::Todos, List::Archive);
+        self.record(Operation::Archive(id), steps);
+
+        Ok(())
+    }
+
+    /// Archives all done [`Todo`]s whose subtasks are all done as well, see
+    /// [`archive_todo`](Self::archive_todo), then returns ids of the archived
+    /// [`Todo`]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::todo::TodoStatus;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let learn = manager.add_todo("Learn Rust");
+    /// manager.add_todo("Build a project");
+    ///
+    /// manager.change_todo_status(learn, TodoStatus::Done).unwrap();
+    ///
+    /// assert_eq!(manager.archive_done_todos(), vec![learn]);
+    /// assert_eq!(manager.all_todos().len(), 1);
+    /// ```
+    pub fn archive_done_todos(&mut self) -> Vec<usize> {
+        let done: BTreeSet<usize> = self
+            .todos
+            .iter()
+            .map(|todo| todo.id())
+            .filter(|id| self.is_subtree_done(*id))
+            .collect();
+
+        let steps = self.move_todos(&done, List::Todos, List::Archive);
+        self.record(Operation::ArchiveDone, steps);
+
+        done.into_iter().collect()
+    }
+
+    /// Returns a [`Vec`] of references to all archived [`Todo`]s.
+    pub fn archived_todos(&self) -> Vec<&Todo> {
+        self.archive.iter().collect()
+    }
+
+    /// Undoes the last change recorded in the [`Journal`] of the
+    /// `TodoManager`, then returns the [`Operation`] that made it.
+    ///
+    /// Every modification of the [`Todo`]s is recorded, apart from adding
+    /// comments and tracking time, which are never undone. Undoing reverts
+    /// only the values the change modified, keeping the other modifications
+    /// of the same [`Todo`]s, and a [`Todo`] removed by undoing its addition
+    /// keeps its comments and logged time when redone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if there is no recorded change to undo, or if the
+    /// [`Todo`]s it modified have changed since without being recorded, e.g.
+    /// by moving them to another project. Such a change is left as it is and
+    /// forgotten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::journal::Operation;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    /// manager
+    ///     .edit_todo_content(id, "Learn Rust like a pro")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(manager.undo(), Ok(Operation::Edit(id)));
+    /// assert_eq!(manager.todo_by_id(id).unwrap().content, "Learn Rust");
+    ///
+    /// assert_eq!(manager.undo(), Ok(Operation::Add(id)));
+    /// assert!(manager.todo_by_id(id).is_none());
+    ///
+    /// // Nothing is left to undo
+    /// assert!(manager.undo().is_err());
+    /// ```
+    pub fn undo(&mut self) -> Result<Operation, TodoError> {
+        let change = self.journal.undo.pop().ok_or(TodoError::NothingToUndo)?;
+        let operation = change.operation();
+
+        let mut steps: Vec<Step> = change.steps().iter().rev().map(Step::inverse).collect();
+
+        if !self.replay(&mut steps) {
+            return Err(TodoError::UndoConflict(operation));
+        }
+
+        let steps = steps.iter().rev().map(Step::inverse).collect();
+        self.journal.redo.push(Change::new(operation, steps));
+
+        Ok(operation)
+    }
+
+    /// Redoes the last change undone by [`undo`](Self::undo), then returns the
+    /// [`Operation`] that made it.
+    ///
+    /// Recording a new change or moving [`Todo`]s to another project makes
+    /// the undone changes impossible to redo.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if there is no undone change to redo, or if the
+    /// [`Todo`]s it modifies have changed since. Such a change is forgotten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::journal::Operation;
+    /// # use doru::todo_manager::TodoManager;
+    /// let mut manager = TodoManager::default();
+    /// let id = manager.add_todo("Learn Rust");
+    ///
+    /// manager.undo().unwrap();
+    /// assert_eq!(manager.redo(), Ok(Operation::Add(id)));
+    /// assert_eq!(manager.todo_by_id(id).unwrap().content, "Learn Rust");
+    ///
+    /// // Nothing is left to redo
+    /// assert!(manager.redo().is_err());
+    /// ```
+    pub fn redo(&mut self) -> Result<Operation, TodoError> {
+        let change = self.journal.redo.pop().ok_or(TodoError::NothingToRedo)?;
+        let operation = change.operation();
+
+        let mut steps = change.steps().to_vec();
+
+        if !self.replay(&mut steps) {
+            return Err(TodoError::RedoConflict(operation));
+        }
+
+        self.journal.undo.push(Change::new(operation, steps));
+
+        Ok(operation)
+    }
+
+    /// Removes a [`Todo`] with the provided id together with all its subtasks
+    /// and returns them, the [`Todo`] itself first.
+    ///
+    /// Dependencies and references between the removed and the remaining
+    /// [`Todo`]s are dropped.
+    pub(crate) fn take_subtree(&mut self, id: usize) -> Result<Vec<Todo>, TodoError> {
+        if self.todo_by_id(id).is_none() {
+            return Err(TodoError::NotFound(id));
+        }
+
+        let subtree = self.subtree_ids(id);
+
+        let (mut taken, kept): (Vec<Todo>, Vec<Todo>) = std::mem::take(&mut self.todos)
+            .into_iter()
+            .partition(|todo| subtree.contains(&todo.id()));
+
+        self.todos = kept;
+        self.forget_dependencies(&subtree);
+        self.forget_references(&subtree);
+        self.journal.forget_undone();
+
+        for todo in &mut taken {
+            todo.depends_on.retain(|id| subtree.contains(id));
+            todo.references.retain(|reference| match reference {
+                Reference::Todo(id) => subtree.contains(id),
+                _ => true,
+            });
+        }
+
+        let root = taken.iter().position(|todo| todo.id() == id).unwrap();
+        taken.swap(0, root);
+
+        Ok(taken)
+    }
+
+    /// Stores [`Todo`]s removed by [`take_subtree`](Self::take_subtree) from
+    /// another `TodoManager` under new ids, then returns the new id of the
+    /// first [`Todo`], which becomes a top-level one, or [`None`] if there
+    /// are no [`Todo`]s.
+    ///
+    /// Parents, dependencies and references between the stored [`Todo`]s
+    /// follow them to the new ids, the ones pointing elsewhere are dropped.
+    pub(crate) fn insert_subtree(&mut self, todos: Vec<Todo>) -> Option<usize> {
+        let mut new_ids = BTreeMap::new();
+
+        for todo in &todos {
+            self.id_counter += 1;
+            new_ids.insert(todo.id(), self.id_counter);
+        }
+
+        let root_id = new_ids[&todos.first()?.id()];
+        let new_id = |id: &usize| new_ids.get(id).copied();
+
+        for mut todo in todos {
+            todo.id = new_ids[&todo.id()];
+            todo.parent = todo.parent.as_ref().and_then(new_id);
+            todo.depends_on = todo.depends_on.iter().filter_map(new_id).collect();
+            todo.references = todo
+                .references
+                .into_iter()
+                .filter_map(|reference| match reference {
+                    Reference::Todo(id) => new_id(&id).map(Reference::Todo),
+                    other => Some(other),
+                })
+                .collect();
+
+            if todo.id == root_id {
+                todo.parent = None;
+            }
+
+            self.todos.push(todo);
+        }
+
+        self.journal.forget_undone();
+
+        Some(root_id)
+    }
+
+    /// Creates a new [`Todo`] with the provided content under the next id and
+    /// stores it, then returns its id.
+    fn create_todo(&mut self, content: &str) -> usize {
+        self.id_counter += 1;
+
+        let mut todo = Todo::new(self.id_counter, content);
+        let now = Utc::now();
+        todo.created = Some(now);
+        todo.updated = Some(now);
+
+        self.todos.push(todo);
+
+        self.id_counter
+    }
+
+    /// Modifies a [`Todo`] with the provided id by the provided function,
+    /// then returns the output of the function together with the [`Step`]s
+    /// setting the modified values.
+    fn track<T>(
+        &mut self,
+        id: usize,
+        modify: impl FnOnce(&mut Todo) -> T,
+    ) -> Result<(T, Vec<Step>), TodoError> {
+        let todo = self.modify_todo(id)?;
+        let before = todo.clone();
+        let output = modify(todo);
+
+        Ok((output, Step::between(&before, todo)))
+    }
+
+    /// Modifies a [`Todo`] with the provided id by the provided function and
+    /// records the change as made by the provided [`Operation`], then returns
+    /// the output of the function.
+    fn update<T>(
+        &mut self,
+        operation: Operation,
+        id: usize,
+        modify: impl FnOnce(&mut Todo) -> T,
+    ) -> Result<T, TodoError> {
+        let (output, steps) = self.track(id, modify)?;
+        self.record(operation, steps);
+
+        Ok(output)
+    }
+
+    /// Records a change made by the provided [`Operation`] in the provided
+    /// [`Step`]s in the [`Journal`], unless it changed nothing.
+    fn record(&mut self, operation: Operation, steps: Vec<Step>) {
+        if !steps.is_empty() {
+            self.journal.record(Change::new(operation, steps));
+        }
+    }
+
+    /// Returns the [`Step`] inserting the [`Todo`] with the provided id at its
+    /// current position, if it exists.
+    fn insertion(&self, id: usize) -> Vec<Step> {
+        self.todos
+            .iter()
+            .position(|todo| todo.id() == id)
+            .map(|index| Step::Insert {
+                list: List::Todos,
+                index,
+                todo: self.todos[index].clone(),
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the provided list of [`Todo`]s.
+    fn list(&self, list: List) -> &Vec<Todo> {
+        match list {
+            List::Todos => &self.todos,
+            List::Archive => &self.archive,
+            List::Trash => &self.trash,
+        }
+    }
+
+    /// Returns a mutable reference to the provided list of [`Todo`]s.
+    fn list_mut(&mut self, list: List) -> &mut Vec<Todo> {
+        match list {
+            List::Todos => &mut self.todos,
+            List::Archive => &mut self.archive,
+            List::Trash => &mut self.trash,
+        }
+    }
+
+    /// Moves the [`Todo`]s with the provided ids from one list to another,
+    /// then returns the [`Step`]s made. They are put to their place by id
+    /// among the active [`Todo`]s, or to the end of the other lists.
+    fn move_todos(&mut self, ids: &BTreeSet<usize>, from: List, to: List) -> Vec<Step> {
+        let mut steps = Vec::new();
+
+        while let Some(index) = self
+            .list(from)
+            .iter()
+            .position(|todo| ids.contains(&todo.id()))
+        {
+            let todo = self.list_mut(from).remove(index);
+            let target = self.list_mut(to);
+            let position = match to {
+                List::Todos => target
+                    .iter()
+                    .position(|other| other.id() > todo.id())
+                    .unwrap_or(target.len()),
+                _ => target.len(),
+            };
+
+            steps.push(Step::Move {
+                id: todo.id(),
+                from: (from, index),
+                to: (to, position),
+            });
+            target.insert(position, todo);
+        }
+
+        steps
+    }
+
+    /// Makes the provided [`Step`]s one by one. If a [`Step`] can't be made,
+    /// reverts the ones made before it and returns `false`.
+    fn replay(&mut self, steps: &mut [Step]) -> bool {
+        for made in 0..steps.len() {
+            if !self.apply(&mut steps[made]) {
+                for step in steps[..made].iter().rev() {
+                    self.apply(&mut step.inverse());
+                }
+
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Makes the provided [`Step`], returning `false` if it can't be made,
+    /// because the [`Todo`]s it modifies don't hold the values it expects.
+    ///
+    /// A [`Todo`] removed by the [`Step`] replaces the recorded one, so that
+    /// its comments and logged time are kept when it is inserted back.
+    fn apply(&mut self, step: &mut Step) -> bool {
+        match step {
+            Step::Insert { list, index, todo } => {
+                let exists = [&self.todos, &self.archive, &self.trash]
+                    .into_iter()
+                    .flatten()
+                    .any(|other| other.id() == todo.id());
+
+                if exists {
+                    return false;
+                }
+
+                let todos = self.list_mut(*list);
+                todos.insert((*index).min(todos.len()), todo.clone());
+            }
+
+            Step::Remove { list, todo, .. } => {
+                let todos = self.list_mut(*list);
+                let Some(index) = todos.iter().position(|other| is_unchanged(other, todo)) else {
+                    return false;
+                };
+
+                *todo = todos.remove(index);
+            }
+
+            Step::Move { id, from, to } => {
+                let source = self.list_mut(from.0);
+                let Some(index) = source.iter().position(|todo| todo.id() == *id) else {
+                    return false;
+                };
+
+                let todo = source.remove(index);
+                let target = self.list_mut(to.0);
+                target.insert(to.1.min(target.len()), todo);
+            }
+
+            Step::Set { id, old, new } => {
+                let todo = [&mut self.todos, &mut self.archive, &mut self.trash]
+                    .into_iter()
+                    .flatten()
+                    .find(|todo| todo.id() == *id);
+
+                match todo {
+                    Some(todo) if old.of(todo) == *old => {
+                        new.write(todo);
+                        todo.updated = Some(Utc::now());
+                    }
+                    _ => return false,
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Moves a [`Todo`] with the provided id together with all its subtasks
+    /// to the trash, stopping their timers, then returns the [`Step`]s made.
+    fn move_to_trash(&mut self, id: usize) -> Vec<Step> {
+        let ids = self.subtree_ids(id);
+
+        let steps = self.move_todos(&ids, List::Todos, List::Trash);
+
+        let now = Utc::now();
+
+        for todo in self
+            .trash
+            .iter_mut()
+            .filter(|todo| ids.contains(&todo.id()))
+        {
+            todo.stop_timer(now);
+            todo.updated = Some(now);
+        }
+
+        steps
+    }
+
+    /// Returns `true` if a [`Todo`] with the provided id and all its subtasks
+    /// are done.
+    fn is_subtree_done(&self, id: usize) -> bool {
+        self.subtree_ids(id)
+            .iter()
+            .all(|id| self.todo_by_id(*id).is_some_and(|todo| self.is_done(todo)))
+    }
+
+    /// Returns ids of a [`Todo`] and all its direct and indirect subtasks.
+    fn subtree_ids(&self, id: usize) -> BTreeSet<usize> {
+        let mut subtree = BTreeSet::from([id]);
+        let mut pending = vec![id];
+
+        while let Some(current) = pending.pop() {
+            for child in self.children(current) {
+                if subtree.insert(child.id()) {
+                    pending.push(child.id());
+                }
+            }
+        }
+
+        subtree
+    }
+
+    /// Creates the occurrence of a repeating [`Todo`] following the finished
+    /// one, due on the provided date, then returns id of the newly created
+    /// [`Todo`].
+    ///
+    /// The next occurrence carries over what describes the work: the parent,
+    /// notes, priority, tags, assignees, fields, references and the checklist
+    /// with all entries unchecked. What describes the finished occurrence, its
+    /// comments, time log, dependencies and snooze, stays with it.
+    fn add_next_occurrence(
+        &mut self,
+        finished: &Todo,
+        recurrence: Recurrence,
+        due: NaiveDate,
+    ) -> usize {
+        let id = self.create_todo(&finished.content);
+        let next = self.todos.last_mut().unwrap();
+
+        next.parent = finished.parent;
+        next.notes = finished.notes.clone();
+        next.priority = finished.priority;
+        next.tags = finished.tags.clone();
+        next.assignees = finished.assignees.clone();
+        next.fields = finished.fields.clone();
+        next.references = finished.references.clone();
+        next.checklist = finished
+            .checklist
+            .iter()
+            .map(|item| ChecklistItem::new(&item.text))
+            .collect();
+        next.recurrence = Some(recurrence);
+        next.due = Some(due);
+        next.scheduled = finished
+            .scheduled
+            .zip(finished.due)
+            .and_then(|(scheduled, old_due)| scheduled.checked_add_signed(due - old_due));
+
+        id
+    }
+
+    /// Returns `true` if a [`Todo`] with the provided id is, or directly or
+    /// indirectly depends on, a [`Todo`] with the provided dependency id.
+    fn depends_transitively(&self, id: usize, dependency_id: usize) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![id];
+
+        while let Some(current) = pending.pop() {
+            if current == dependency_id {
+                return true;
+            }
+
+            if visited.insert(current) {
+                if let Some(todo) = self.todo_by_id(current) {
+                    pending.extend(todo.dependencies());
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Removes dependencies on the provided ids from all [`Todo`]s, archived
+    /// ones included, then returns the [`Step`]s made.
+    fn forget_dependencies(&mut self, ids: &BTreeSet<usize>) -> Vec<Step> {
+        let mut steps = Vec::new();
+
+        for todo in self.todos.iter_mut().chain(&mut self.archive) {
+            for dependency_id in todo.depends_on.intersection(ids) {
+                steps.push(Step::Set {
+                    id: todo.id(),
+                    old: Value::Dependency(*dependency_id, true),
+                    new: Value::Dependency(*dependency_id, false),
+                });
+            }
+
+            todo.depends_on.retain(|id| !ids.contains(id));
+        }
+
+        steps
+    }
+
+    /// Removes references to [`Todo`]s with the provided ids from all
+    /// [`Todo`]s, archived ones included, then returns the [`Step`]s made.
+    fn forget_references(&mut self, ids: &BTreeSet<usize>) -> Vec<Step> {
+        let mut steps = Vec::new();
+
+        for todo in self.todos.iter_mut().chain(&mut self.archive) {
+            let old = todo.references.clone();
+
+            todo.references.retain(|reference| match reference {
+                Reference::Todo(id) => !ids.contains(id),
+                _ => true,
+            });
+
+            if todo.references != old {
+                steps.push(Step::Set {
+                    id: todo.id(),
+                    old: Value::References(old),
+                    new: Value::References(todo.references.clone()),
+                });
+            }
+        }
+
+        steps
+    }
+
+    fn progress(&self, todo: &Todo) -> f64 {
+        let children = self.children(todo.id());
+
+        if children.is_empty() {
+            return if self.is_done(todo) { 1.0 } else { 0.0 };
+        }
+
+        let total: f64 = children.iter().map(|child| self.progress(child)).sum();
+        total / children.len() as f64
+    }
+
+    /// Checks that a [`Todo`] with the provided id has a checklist entry at
+    /// the provided index.
+    fn check_checklist_index(&self, id: usize, index: usize) -> Result<(), TodoError> {
+        let todo = self.todo_by_id(id).ok_or(TodoError::NotFound(id))?;
+
+        if index >= todo.checklist.len() {
+            return Err(TodoError::ChecklistItemNotFound(id, index));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a mutable reference to a [`Todo`] with the provided id, marking
+    /// it as updated now.
+    fn modify_todo(&mut self, id: usize) -> Result<&mut Todo, TodoError> {
+        let todo = self
+            .todos
+            .iter_mut()
+            .find(|todo| todo.id() == id)
+            .ok_or(TodoError::NotFound(id))?;
+
+        todo.updated = Some(Utc::now());
+        Ok(todo)
+    }
+}
+
+/// Returns the highest id of the provided [`Todo`]s, or 0 if there are none.
+fn last_id(todos: &[Todo]) -> usize {
+    todos.iter().map(|todo| todo.id()).max().unwrap_or(0)
+}
+
+/// Returns `true` if the [`Todo`] is the same as the recorded one, apart from
+/// the time it was updated and its comments and logged time, which are never
+/// recorded.
+fn is_unchanged(todo: &Todo, recorded: &Todo) -> bool {
+    let todo = Todo {
+        updated: recorded.updated,
+        comments: recorded.comments.clone(),
+        time_log: recorded.time_log.clone(),
+        ..todo.clone()
+    };
+
+    todo == *recorded
+}
+
+/// Returns ids of a [`Todo`] with the provided id and of all its subtasks
+/// among the provided [`Todo`]s.
+fn subtree_in(todos: &[Todo], id: usize) -> BTreeSet<usize> {
+    let mut subtree = BTreeSet::from([id]);
+    let mut pending = vec![id];
+
+    while let Some(current) = pending.pop() {
+        for todo in todos.iter().filter(|todo| todo.parent() == Some(current)) {
+            if subtree.insert(todo.id()) {
+                pending.push(todo.id());
+            }
+        }
+    }
+
+    subtree
+}
+
+/// Returns the [`Todo`] whose content matches the text the closest, see
+/// [`TodoManager::find_todo`].
+fn select<'a>(todos: &'a [Todo], text: &str) -> Result<&'a Todo, TodoError> {
+    let Ok(query) = text.parse::<SearchQuery>();
+
+    let candidates: Vec<(Closeness, &Todo)> = todos
+        .iter()
+        .filter_map(|todo| Some((query.closeness(&todo.content)?, todo)))
+        .collect();
+
+    let best = candidates
+        .iter()
+        .map(|(closeness, _)| *closeness)
+        .max()
+        .ok_or_else(|| TodoError::NoMatch(String::from(text)))?;
+
+    let closest: Vec<&Todo> = candidates
+        .into_iter()
+        .filter(|(closeness, _)| *closeness == best)
+        .map(|(_, todo)| todo)
+        .collect();
+
+    match closest[..] {
+        [todo] => Ok(todo),
+        _ => Err(TodoError::AmbiguousSelector(
+            String::from(text),
+            closest
+                .iter()
+                .map(|todo| (todo.id(), todo.content.clone()))
+                .collect(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workflow::CustomStatus;
+
+    #[test]
+    fn new_from_empty_vec_is_empty() {
+        let manager = TodoManager::new(vec![]);
+
+        assert_eq!(manager.todos, vec![]);
+    }
+
+    #[test]
+    fn new_from_empty_has_last_counter_0() {
+        let manager = TodoManager::new(vec![]);
+
+        assert_eq!(manager.id_counter, 0);
+    }
+
+    #[test]
+    fn new_from_existing_is_filled() {
+        let todos = vec![Todo::new(0, "Lorem"), Todo::new(1, "Ipsum")];
+
+        let manager = TodoManager::new(todos.clone());
+
+        assert_eq!(manager.todos, todos);
+    }
+
+    #[test]
+    fn new_from_existing_has_correct_id_counter() {
+        let todos = vec![
+            Todo::new(0, "Lorem"),
+            Todo::new(10, "Ipsum"),
+            Todo::new(2, "Dolor"),
+        ];
+
+        let manager = TodoManager::new(todos.clone());
+
+        assert_eq!(manager.id_counter, 10);
+    }
+
+    #[test]
+    fn id_counter_is_not_lowered_below_existing_ids() {
+        let todos = vec![Todo::new(1, "Lorem"), Todo::new(10, "Ipsum")];
+
+        let manager = TodoManager::new(todos).with_id_counter(4);
+
+        assert_eq!(manager.id_counter(), 10);
+    }
+
+    #[test]
+    fn add_todo_adds_todo() {
+        let mut manager = TodoManager::default();
+
+        let content = "Lorem Ipsum";
+        manager.add_todo(content);
+
+        assert_eq!(manager.todos.len(), 1);
+        assert_eq!(manager.todos[0].content, content);
+        assert_eq!(manager.todos[0].status, TodoStatus::Open)
+    }
+
+    #[test]
+    fn add_todo_returns_correct_id() {
+        let mut manager = TodoManager::default();
+
+        let new_id = manager.add_todo("content");
+        assert_eq!(new_id, 1);
+
+        let new_id = manager.add_todo("another");
+        assert_eq!(new_id, 2);
+    }
+
+    #[test]
+    fn ids_increment() {
+        let mut manager = TodoManager::default();
+
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+
+        assert_eq!(manager.todos[1].id(), 2);
+    }
+
+    #[test]
+    fn get_open_todos() {
+        let mut manager = TodoManager::default();
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
+
+        manager.todos[2].status = TodoStatus::InProgress;
+
+        let open_todos = manager.todos_by_status(TodoStatus::Open);
+
+        assert_eq!(open_todos.len(), 2);
+
+        assert_eq!(open_todos, vec![&manager.todos[0], &manager.todos[1]]);
+    }
+
+    #[test]
+    fn get_in_progress_todos() {
+        let mut manager = TodoManager::default();
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
+
+        manager.todos[1].status = TodoStatus::InProgress;
+
+        let in_progress = manager.todos_by_status(TodoStatus::InProgress);
+
+        assert_eq!(in_progress.len(), 1);
+
+        assert_eq!(in_progress, vec![&manager.todos[1]]);
+    }
+
+    #[test]
+    fn get_all_todos() {
+        let mut manager = TodoManager::default();
+
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
+        manager.add_todo("Sit");
+
+        let todos = manager.all_todos();
+
+        assert_eq!(todos.len(), 4)
+    }
+
+    #[test]
+    fn get_existing_todo_by_id() {
+        let mut manager = TodoManager::default();
+
+        manager.add_todo("This has id 1");
+        manager.add_todo("This has id 2");
+        let id = manager.add_todo("This has id 3");
+
+        let todo = manager.todo_by_id(id);
+
+        assert_eq!(todo, Some(&manager.todos[2]))
+    }
+
+    #[test]
+    fn try_getting_non_existent_todo_by_id() {
+        let manager = TodoManager::default();
+
+        let todo = manager.todo_by_id(42);
+
+        assert!(todo.is_none())
+    }
+
+    #[test]
+    fn edit_existing_todo_content_succeeds() {
+        let mut manager = TodoManager::default();
+
+        let new_id = manager.add_todo("This is a nice TODO.");
+
+        let test_content = "This is even better!";
+        let result = manager.edit_todo_content(new_id, test_content);
+
+        assert_eq!(result, Ok(()));
+
+        let updated_content = &manager.todo_by_id(new_id).unwrap().content;
+        assert_eq!(updated_content, test_content)
+    }
+
+    #[test]
+    fn edit_nonexistent_todo_content_fails() {
+        let mut manager = TodoManager::default();
+
+        let result = manager.edit_todo_content(1, "Some content.");
+        assert_eq!(result, Err(TodoError::NotFound(1)))
+    }
+
+    #[test]
+    fn append_notes_to_empty_notes_has_no_leading_newline() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        manager.append_todo_notes(id, "Ipsum").unwrap();
+        assert_eq!(manager.todos[0].notes, "Ipsum");
+
+        manager.append_todo_notes(id, "Dolor").unwrap();
+        assert_eq!(manager.todos[0].notes, "Ipsum\nDolor");
+    }
+
+    #[test]
+    fn set_notes_of_nonexistent_todo_fails() {
+        let mut manager = TodoManager::default();
+
+        let result = manager.set_todo_notes(42, "Lorem");
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+    }
+
+    #[test]
+    fn comments_are_appended_in_order_with_current_time() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        let before = Utc::now();
+
+        manager.add_comment(id, "Ipsum", Some("alice")).unwrap();
+        manager.add_comment(id, "Dolor", None).unwrap();
+
+        let todo = &manager.todos[0];
+        let texts: Vec<_> = todo.comments().iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["Ipsum", "Dolor"]);
+        assert_eq!(todo.comments()[1].author, None);
+        assert!(before <= todo.comments()[0].time);
+        assert!(todo.comments()[0].time <= todo.comments()[1].time);
+        assert!(todo.comments()[1].time <= Utc::now());
+    }
+
+    #[test]
+    fn change_existing_todo_status_succeeds() {
+        let mut manager = TodoManager::default();
+        let new_id = manager.add_todo("Good to do.");
+
+        let new_state = TodoStatus::InProgress;
+
+        let result = manager.change_todo_status(new_id, new_state.clone());
+        assert_eq!(result, Ok(()));
+
+        let updated_state = &manager.todo_by_id(new_id).unwrap().status;
+        assert_eq!(*updated_state, new_state);
+    }
+
+    #[test]
+    fn change_nonexistent_todo_status_fails() {
+        let mut manager = TodoManager::default();
+
+        let result = manager.change_todo_status(42, TodoStatus::Done);
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+    }
+
+    #[test]
+    fn delete_existing_todo_succeeds() {
+        let mut manager = TodoManager::default();
+        let new_id = manager.add_todo("Lorem Ipsum");
+
+        let result = manager.delete_todo(new_id);
+        assert_eq!(result, Ok(()));
+
+        assert!(manager.todos.is_empty());
+    }
+
+    #[test]
+    fn delete_nonexistent_todo_fails() {
+        let mut manager = TodoManager::default();
+
+        let result = manager.delete_todo(42);
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+    }
+
+    #[test]
+    fn change_existing_todo_priority_succeeds() {
+        let mut manager = TodoManager::default();
+        let new_id = manager.add_todo("Lorem");
+
+        let result = manager.set_todo_priority(new_id, Priority::High);
+        assert_eq!(result, Ok(()));
+
+        assert_eq!(manager.todos[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn todos_by_priority_is_stable() {
+        let mut manager = TodoManager::default();
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
+        manager.add_todo("Sit");
+
+        manager.todos[1].priority = Priority::Low;
+        manager.todos[3].priority = Priority::Urgent;
+
+        let todos = manager.todos_by_priority();
+
+        assert_eq!(
+            todos,
+            vec![
+                &manager.todos[3],
+                &manager.todos[0],
+                &manager.todos[2],
+                &manager.todos[1]
+            ]
+        );
+    }
+
+    #[test]
+    fn add_tag_to_nonexistent_todo_fails() {
+        let mut manager = TodoManager::default();
+
+        let result = manager.add_todo_tag(42, "lorem");
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+    }
+
+    #[test]
+    fn remove_tag_removes_only_given_tag() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        manager.add_todo_tag(id, "ipsum").unwrap();
+        manager.add_todo_tag(id, "dolor").unwrap();
+        manager.remove_todo_tag(id, "ipsum").unwrap();
+
+        assert!(!manager.todos[0].has_tag("ipsum"));
+        assert!(manager.todos[0].has_tag("dolor"));
+    }
+
+    #[test]
+    fn all_tags_counts_todos() {
+        let mut manager = TodoManager::default();
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+
+        manager.add_todo_tag(1, "dolor").unwrap();
+        manager.add_todo_tag(2, "dolor").unwrap();
+        manager.add_todo_tag(2, "sit").unwrap();
+
+        let tags = manager.all_tags();
+
+        assert_eq!(tags, BTreeMap::from([("dolor", 2), ("sit", 1)]));
+    }
+
+    #[test]
+    fn todos_with_tags_match_any_or_all() {
+        let mut manager = TodoManager::default();
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
+
+        manager.add_todo_tag(1, "sit").unwrap();
+        manager.add_todo_tag(2, "sit").unwrap();
+        manager.add_todo_tag(2, "amet").unwrap();
+
+        let any = manager.todos_with_any_tag(&["sit", "amet"]);
+        assert_eq!(any, vec![&manager.todos[0], &manager.todos[1]]);
+
+        let all = manager.todos_with_all_tags(&["sit", "amet"]);
+        assert_eq!(all, vec![&manager.todos[1]]);
+    }
+
+    #[test]
+    fn add_subtask_to_nonexistent_parent_fails() {
+        let mut manager = TodoManager::default();
+
+        let result = manager.add_subtask(42, "Lorem");
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+        assert!(manager.todos.is_empty());
+    }
+
+    #[test]
+    fn children_are_only_direct_subtasks() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
+        manager.add_subtask(child, "Dolor").unwrap();
+
+        assert_eq!(manager.children(parent), vec![&manager.todos[1]]);
+    }
+
+    #[test]
+    fn move_subtree_under_own_subtask_fails() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
+        let grandchild = manager.add_subtask(child, "Dolor").unwrap();
+
+        let result = manager.move_subtree(parent, Some(grandchild));
+        assert_eq!(result, Err(TodoError::InvalidParent(parent, grandchild)));
+
+        let result = manager.move_subtree(parent, Some(parent));
+        assert_eq!(result, Err(TodoError::InvalidParent(parent, parent)));
+    }
+
+    #[test]
+    fn move_subtree_to_top_level_succeeds() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
+
+        let result = manager.move_subtree(child, None);
+        assert_eq!(result, Ok(()));
+
+        assert!(manager.children(parent).is_empty());
+    }
+
+    #[test]
+    fn delete_todo_with_subtasks_fails() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        manager.add_subtask(parent, "Ipsum").unwrap();
+
+        let result = manager.delete_todo(parent);
+        assert_eq!(result, Err(TodoError::HasSubtasks(parent)));
+        assert_eq!(manager.todos.len(), 2);
+    }
+
+    #[test]
+    fn delete_todo_cascade_deletes_whole_subtree() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
+        manager.add_subtask(child, "Dolor").unwrap();
+        let other = manager.add_todo("Sit");
+
+        let result = manager.delete_todo_cascade(parent);
+        assert_eq!(result, Ok(()));
+
+        assert_eq!(
+            manager.all_todos(),
+            vec![manager.todo_by_id(other).unwrap()]
+        );
+    }
+
+    #[test]
+    fn progress_rolls_up_from_subtasks() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let first = manager.add_subtask(parent, "Ipsum").unwrap();
+        manager.add_subtask(parent, "Dolor").unwrap();
+        let grandchild = manager.add_subtask(first, "Sit").unwrap();
+        manager.add_subtask(first, "Amet").unwrap();
+
+        manager
+            .change_todo_status(grandchild, TodoStatus::Done)
+            .unwrap();
+
+        assert_eq!(manager.todo_progress(first), Ok(0.5));
+        assert_eq!(manager.todo_progress(parent), Ok(0.25));
+    }
+
+    #[test]
+    fn add_dependency_on_nonexistent_todo_fails() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        let result = manager.add_dependency(id, 42);
+        assert_eq!(result, Err(TodoError::MissingDependency(42)));
+    }
+
+    #[test]
+    fn add_dependency_on_itself_fails() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        let result = manager.add_dependency(id, id);
+        assert_eq!(result, Err(TodoError::DependencyCycle(id, id)));
+    }
+
+    #[test]
+    fn add_indirectly_cyclic_dependency_fails() {
+        let mut manager = TodoManager::default();
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+        let third = manager.add_todo("Dolor");
+
+        manager.add_dependency(second, first).unwrap();
+        manager.add_dependency(third, second).unwrap();
+
+        let result = manager.add_dependency(first, third);
+        assert_eq!(result, Err(TodoError::DependencyCycle(first, third)));
+    }
+
+    #[test]
+    fn finishing_blocked_todo_fails() {
+        let mut manager = TodoManager::default();
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+        let third = manager.add_todo("Dolor");
+
+        manager.add_dependency(third, first).unwrap();
+        manager.add_dependency(third, second).unwrap();
+        manager
+            .change_todo_status(second, TodoStatus::Done)
+            .unwrap();
+
+        let result = manager.change_todo_status(third, TodoStatus::Done);
+        assert_eq!(result, Err(TodoError::Blocked(third, vec![first])));
+        assert_eq!(manager.todos[2].status, TodoStatus::Open);
+
+        let result = manager.change_todo_status(third, TodoStatus::InProgress);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn ready_todos_have_all_dependencies_done() {
+        let mut manager = TodoManager::default();
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+        let third = manager.add_todo("Dolor");
+
+        manager.add_dependency(second, first).unwrap();
+        manager.add_dependency(third, second).unwrap();
+        manager.change_todo_status(first, TodoStatus::Done).unwrap();
+
+        assert_eq!(manager.ready_todos(), vec![&manager.todos[1]]);
+    }
+
+    #[test]
+    fn deleting_dependency_unblocks_dependent_todos() {
+        let mut manager = TodoManager::default();
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+
+        manager.add_dependency(second, first).unwrap();
+        manager.delete_todo(first).unwrap();
+
+        assert!(manager.blockers(second).unwrap().is_empty());
+        assert_eq!(manager.ready_todos(), vec![&manager.todos[0]]);
+    }
+
+    #[test]
+    fn delete_and_restore_keeps_dependencies_and_references() {
+        let mut manager = TodoManager::default();
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+
+        manager.add_dependency(second, first).unwrap();
+        manager
+            .add_todo_reference(second, Reference::Todo(first))
+            .unwrap();
+        let before = manager.todos.clone();
+
+        manager.delete_todo(first).unwrap();
+        assert_eq!(manager.todos[0].dependencies(), &BTreeSet::from([first]));
+        assert_eq!(manager.todos[0].references, vec![Reference::Todo(first)]);
+
+        manager.restore_todo(first).unwrap();
+        assert!(manager
+            .todos
+            .iter()
+            .zip(&before)
+            .all(|(todo, recorded)| is_unchanged(todo, recorded)));
+        assert_eq!(manager.blockers(second).unwrap(), vec![&manager.todos[0]]);
+    }
+
+    #[test]
+    fn emptying_trash_forgets_dependencies_and_references() {
+        let mut manager = TodoManager::default();
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+
+        manager.add_dependency(second, first).unwrap();
+        manager
+            .add_todo_reference(second, Reference::Todo(first))
+            .unwrap();
+        manager.delete_todo(first).unwrap();
+
+        assert_eq!(manager.empty_trash(), 1);
+        assert!(manager.todos[0].dependencies().is_empty());
+        assert!(manager.todos[0].references.is_empty());
+
+        assert_eq!(manager.undo(), Ok(Operation::EmptyTrash));
+        assert_eq!(manager.todos[0].dependencies(), &BTreeSet::from([first]));
+        assert_eq!(manager.todos[0].references, vec![Reference::Todo(first)]);
+        assert_eq!(manager.trashed_todos()[0].id(), first);
+    }
+
+    #[test]
+    fn add_todo_stamps_creation_time() {
+        let mut manager = TodoManager::default();
+
+        let before = Utc::now();
+        manager.add_todo("Lorem");
+        let after = Utc::now();
+
+        let created = manager.todos[0].created().unwrap();
+        assert!(before <= created && created <= after);
+        assert_eq!(manager.todos[0].updated(), Some(created));
+        assert_eq!(manager.todos[0].completed(), None);
+    }
+
+    #[test]
+    fn edit_todo_content_stamps_update_time() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.todos[0].updated = None;
+
+        manager.edit_todo_content(id, "Ipsum").unwrap();
+
+        assert!(manager.todos[0].updated().is_some());
+    }
+
+    #[test]
+    fn completion_time_is_kept_until_todo_is_reopened() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+        let completed = manager.todos[0].completed();
+        assert!(completed.is_some());
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+        assert_eq!(manager.todos[0].completed(), completed);
+
+        manager.change_todo_status(id, TodoStatus::Open).unwrap();
+        assert_eq!(manager.todos[0].completed(), None);
+    }
+
+    #[test]
+    fn todos_completed_since_skip_older_completions() {
+        let mut manager = TodoManager::default();
+        manager.add_todo("Lorem");
+        manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
+
+        let now = Utc::now();
+        manager.todos[0].completed = Some(now - chrono::TimeDelta::days(8));
+        manager.todos[1].completed = Some(now - chrono::TimeDelta::days(6));
+
+        let completed = manager.todos_completed_since(now - chrono::TimeDelta::days(7));
+
+        assert_eq!(completed, vec![&manager.todos[1]]);
+    }
 
     #[test]
-    fn add_todo_adds_todo() {
+    fn finishing_repeating_todo_creates_next_occurrence() {
         let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        let today = Local::now().date_naive();
+        let days = chrono::TimeDelta::days;
 
-        let content = "Lorem Ipsum";
-        manager.add_todo(content);
+        manager.todos[0].due = Some(today);
+        manager.todos[0].scheduled = Some(today - days(1));
+        manager.todos[0].priority = Priority::High;
+        manager.todos[0].recurrence = Some(Recurrence::Weekly);
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+        assert_eq!(manager.todos.len(), 2);
+        assert_eq!(manager.todos[0].recurrence, None);
+
+        let next = &manager.todos[1];
+        assert_eq!(next.content, "Lorem");
+        assert_eq!(next.status, TodoStatus::Open);
+        assert_eq!(next.priority, Priority::High);
+        assert_eq!(next.due, Some(today + days(7)));
+        assert_eq!(next.scheduled, Some(today + days(6)));
+        assert_eq!(next.recurrence, Some(Recurrence::Weekly));
+    }
+
+    #[test]
+    fn finishing_overdue_repeating_todo_skips_missed_occurrences() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        let today = Local::now().date_naive();
+        let days = chrono::TimeDelta::days;
+
+        manager.todos[0].due = Some(today - days(10));
+        manager.todos[0].recurrence = Some(Recurrence::Weekly);
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+        assert_eq!(manager.todos[1].due, Some(today + days(4)));
+        assert!(manager.overdue_todos(today).is_empty());
+    }
+
+    #[test]
+    fn monthly_occurrences_keep_day_of_month() {
+        let mut manager = TodoManager::default();
+        let mut id = manager.add_todo("Lorem");
+
+        manager.todos[0].due = NaiveDate::from_ymd_opt(2100, 1, 31);
+        manager.todos[0].recurrence = Some(Recurrence::Monthly);
+
+        let mut dues = vec![];
+        for _ in 0..3 {
+            manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+            let next = manager.todos.last().unwrap();
+            id = next.id();
+            dues.push(next.due.unwrap());
+        }
+
+        let day = |month, day| NaiveDate::from_ymd_opt(2100, month, day).unwrap();
+        assert_eq!(dues, vec![day(2, 28), day(3, 31), day(4, 30)]);
+    }
+
+    #[test]
+    fn repeating_after_completion_counts_from_today() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        manager.todos[0].due = Some(date(2));
+        manager.todos[0].recurrence = Some(Recurrence::AfterCompletion(3));
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+        let today = Local::now().date_naive();
+        assert_eq!(
+            manager.todos[1].due,
+            Some(today + chrono::TimeDelta::days(3))
+        );
+    }
+
+    #[test]
+    fn finishing_done_repeating_todo_again_creates_nothing() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.todos[0].recurrence = Some(Recurrence::Daily);
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+        assert_eq!(manager.todos.len(), 2);
+    }
+
+    #[test]
+    fn next_occurrence_carries_over_work_but_not_history() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        let dependency = manager.add_todo("Ipsum");
+        manager
+            .change_todo_status(dependency, TodoStatus::Done)
+            .unwrap();
+
+        manager.assign_todo(id, "alice").unwrap();
+        manager
+            .set_todo_field(id, "cost", FieldValue::Number(5.0))
+            .unwrap();
+        manager
+            .add_todo_reference(id, Reference::Todo(dependency))
+            .unwrap();
+        manager.add_checklist_item(id, "Dolor").unwrap();
+        manager.check_checklist_item(id, 0).unwrap();
+        manager.add_comment(id, "Sit", None).unwrap();
+        manager.add_dependency(id, dependency).unwrap();
+        manager
+            .set_todo_recurrence(id, Some(Recurrence::Daily))
+            .unwrap();
 
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+        let next = manager.todos.last().unwrap();
+        assert!(next.is_assigned_to("alice"));
+        assert_eq!(next.fields, manager.todos[0].fields);
+        assert_eq!(next.references, vec![Reference::Todo(dependency)]);
+        assert_eq!(next.checklist, vec![ChecklistItem::new("Dolor")]);
+        assert!(next.comments().is_empty());
+        assert!(next.dependencies().is_empty());
+    }
+
+    #[test]
+    fn finishing_todo_repeating_out_of_date_range_fails() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.todos[0].recurrence = Some("every:4000000000".parse().unwrap());
+
+        assert_eq!(
+            manager.change_todo_status(id, TodoStatus::Done),
+            Err(TodoError::RecurrenceOutOfRange(id))
+        );
         assert_eq!(manager.todos.len(), 1);
-        assert_eq!(manager.todos[0].content, content);
-        assert_eq!(manager.todos[0].status, TodoStatus::Open)
+        assert_eq!(manager.todos[0].status, TodoStatus::Open);
+    }
+
+    fn custom(name: &str) -> TodoStatus {
+        TodoStatus::Custom(String::from(name))
     }
 
     #[test]
-    fn add_todo_returns_correct_id() {
+    fn change_status_to_undefined_custom_status_fails() {
         let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
 
-        let new_id = manager.add_todo("content");
-        assert_eq!(new_id, 1);
+        let result = manager.change_todo_status(id, custom("Review"));
+        assert_eq!(
+            result,
+            Err(TodoError::UnknownStatus(String::from("Review")))
+        );
+    }
 
-        let new_id = manager.add_todo("another");
-        assert_eq!(new_id, 2);
+    #[test]
+    fn change_status_to_custom_status_uses_defined_spelling() {
+        let workflow = Workflow::new(vec![CustomStatus::new("Review")]);
+        let mut manager = TodoManager::default().with_workflow(workflow);
+        let id = manager.add_todo("Lorem");
+
+        manager.change_todo_status(id, custom("REVIEW")).unwrap();
+
+        assert_eq!(manager.todos[0].status, custom("Review"));
+        assert!(!manager.is_done(&manager.todos[0]));
+        assert_eq!(manager.todos_by_status(custom("review")).len(), 1);
     }
 
     #[test]
-    fn ids_increment() {
+    fn custom_done_status_completes_todo() {
+        let workflow = Workflow::new(vec![CustomStatus::done("Won't do")]);
+        let mut manager = TodoManager::default().with_workflow(workflow);
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+        manager.add_dependency(second, first).unwrap();
+
+        manager
+            .change_todo_status(first, custom("Won't do"))
+            .unwrap();
+
+        assert!(manager.todos[0].completed().is_some());
+        assert_eq!(manager.ready_todos(), vec![&manager.todos[1]]);
+
+        manager.change_todo_status(first, TodoStatus::Open).unwrap();
+        assert!(!manager.is_done(&manager.todos[0]));
+    }
+
+    #[test]
+    fn doneness_of_custom_status_follows_workflow() {
+        let workflow = Workflow::new(vec![CustomStatus::done("Won't do")]);
+        let mut manager = TodoManager::default().with_workflow(workflow);
+        let first = manager.add_todo("Lorem");
+        let second = manager.add_todo("Ipsum");
+        manager.add_dependency(second, first).unwrap();
+
+        manager
+            .change_todo_status(first, custom("Won't do"))
+            .unwrap();
+        manager.todos[0].status = TodoStatus::Open;
+
+        assert!(manager.todos[0].completed().is_some());
+        assert!(!manager.is_done(&manager.todos[0]));
+        assert_eq!(manager.ready_todos(), vec![&manager.todos[0]]);
+
+        manager.todos[0].status = custom("Won't do");
+        let workflow = Workflow::new(vec![CustomStatus::new("Won't do")]);
+        let manager = manager.with_workflow(workflow);
+
+        assert!(!manager.is_done(&manager.todos[0]));
+        assert_eq!(manager.blockers(second).unwrap(), vec![&manager.todos[0]]);
+    }
+
+    #[test]
+    fn any_transition_is_allowed_by_default() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+        manager.change_todo_status(id, TodoStatus::Open).unwrap();
+
+        assert_eq!(manager.todos[0].status, TodoStatus::Open);
+    }
+
+    #[test]
+    fn transition_rejected_by_policy_fails() {
+        let policy =
+            TransitionPolicy::default().allow_only(TodoStatus::Done, vec![TodoStatus::InProgress]);
+        let mut manager = TodoManager::default().with_transition_policy(policy);
+        let id = manager.add_todo("Lorem");
+
+        let result = manager.change_todo_status(id, TodoStatus::Done);
+        assert_eq!(
+            result,
+            Err(TodoError::InvalidTransition(
+                id,
+                TodoStatus::Open,
+                TodoStatus::Done
+            ))
+        );
+        assert_eq!(manager.todos[0].status, TodoStatus::Open);
+    }
+
+    #[test]
+    fn leaving_custom_done_status_requires_reopen() {
+        let workflow = Workflow::new(vec![CustomStatus::done("Won't do")]);
+        let policy = TransitionPolicy::default().require_reopen();
+        let mut manager = TodoManager::default()
+            .with_workflow(workflow)
+            .with_transition_policy(policy);
+        let id = manager.add_todo("Lorem");
+
+        manager.change_todo_status(id, custom("Won't do")).unwrap();
+
+        let result = manager.change_todo_status(id, TodoStatus::InProgress);
+        assert_eq!(
+            result,
+            Err(TodoError::InvalidTransition(
+                id,
+                custom("Won't do"),
+                TodoStatus::InProgress
+            ))
+        );
+
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+        manager.reopen_todo(id, TodoStatus::InProgress).unwrap();
+        assert!(!manager.is_done(&manager.todos[0]));
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, day).unwrap()
+    }
+
+    #[test]
+    fn set_due_date_of_nonexistent_todo_fails() {
         let mut manager = TodoManager::default();
 
+        let result = manager.set_todo_due_date(42, Some(date(1)));
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+    }
+
+    #[test]
+    fn overdue_todos_skip_done_and_undated() {
+        let mut manager = TodoManager::default();
         manager.add_todo("Lorem");
         manager.add_todo("Ipsum");
+        manager.add_todo("Dolor");
 
-        assert_eq!(manager.todos[1].id(), 2);
+        manager.todos[0].due = Some(date(10));
+        manager.todos[1].due = Some(date(10));
+        manager.todos[1].status = TodoStatus::Done;
+
+        let overdue = manager.overdue_todos(date(11));
+
+        assert_eq!(overdue, vec![&manager.todos[0]]);
     }
 
     #[test]
-    fn get_open_todos() {
+    fn todos_due_between_is_inclusive() {
         let mut manager = TodoManager::default();
         manager.add_todo("Lorem");
         manager.add_todo("Ipsum");
         manager.add_todo("Dolor");
 
-        manager.todos[2].status = TodoStatus::InProgress;
-
-        let open_todos = manager.todos_by_status(TodoStatus::Open);
+        manager.todos[0].due = Some(date(1));
+        manager.todos[1].due = Some(date(5));
+        manager.todos[2].due = Some(date(6));
 
-        assert_eq!(open_todos.len(), 2);
+        let due = manager.todos_due_between(date(1), date(5));
 
-        assert_eq!(open_todos, vec![&manager.todos[0], &manager.todos[1]]);
+        assert_eq!(due, vec![&manager.todos[0], &manager.todos[1]]);
     }
 
     #[test]
-    fn get_in_progress_todos() {
+    fn todos_due_before_is_exclusive() {
         let mut manager = TodoManager::default();
         manager.add_todo("Lorem");
         manager.add_todo("Ipsum");
-        manager.add_todo("Dolor");
 
-        manager.todos[1].status = TodoStatus::InProgress;
+        manager.todos[0].due = Some(date(1));
+        manager.todos[1].due = Some(date(5));
 
-        let in_progress = manager.todos_by_status(TodoStatus::InProgress);
+        let due = manager.todos_due_before(date(5));
 
-        assert_eq!(in_progress.len(), 1);
+        assert_eq!(due, vec![&manager.todos[0]]);
+    }
 
-        assert_eq!(in_progress, vec![&manager.todos[1]]);
+    #[test]
+    fn starting_running_timer_again_fails() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+
+        manager.start_timer(id).unwrap();
+
+        assert_eq!(manager.start_timer(id), Err(TodoError::TimerRunning(id)));
+        assert_eq!(manager.todos[0].time_entries().len(), 1);
     }
 
     #[test]
-    fn get_all_todos() {
+    fn finishing_todo_stops_its_timer() {
         let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
 
-        manager.add_todo("Lorem");
-        manager.add_todo("Ipsum");
+        manager.start_timer(id).unwrap();
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+
+        assert!(manager.running_timer().is_none());
+        assert!(manager.todos[0].time_entries()[0].end.is_some());
+    }
+
+    #[test]
+    fn restore_subtask_of_deleted_parent_makes_it_top_level() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
+
+        manager.delete_todo(child).unwrap();
+        manager.delete_todo(parent).unwrap();
+        manager.restore_todo(child).unwrap();
+
+        assert_eq!(manager.todo_by_id(child).unwrap().parent(), None);
+        assert_eq!(manager.trashed_todos().len(), 1);
+    }
+
+    #[test]
+    fn restore_cascade_deleted_todo_restores_subtasks() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
         manager.add_todo("Dolor");
-        manager.add_todo("Sit");
 
-        let todos = manager.all_todos();
+        manager.delete_todo_cascade(parent).unwrap();
+        manager.restore_todo(parent).unwrap();
 
-        assert_eq!(todos.len(), 4)
+        let ids: Vec<usize> = manager.all_todos().iter().map(|todo| todo.id()).collect();
+        assert_eq!(ids, vec![parent, child, 3]);
+        assert_eq!(
+            manager.restore_todo(parent),
+            Err(TodoError::NotFound(parent))
+        );
     }
 
     #[test]
-    fn get_existing_todo_by_id() {
+    fn restore_todo_keeps_order_of_other_todos() {
+        let mut manager = TodoManager::new(vec![
+            Todo::new(1, "Lorem"),
+            Todo::new(3, "Ipsum"),
+            Todo::new(2, "Dolor"),
+        ]);
+
+        manager.delete_todo(1).unwrap();
+        manager.restore_todo(1).unwrap();
+
+        let ids: Vec<usize> = manager.all_todos().iter().map(|todo| todo.id()).collect();
+        assert_eq!(ids, vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn empty_trash_removes_todos_until_undone() {
         let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.delete_todo(id).unwrap();
 
-        manager.add_todo("This has id 1");
-        manager.add_todo("This has id 2");
-        let id = manager.add_todo("This has id 3");
+        assert_eq!(manager.empty_trash(), 1);
+        assert_eq!(manager.restore_todo(id), Err(TodoError::NotFound(id)));
 
-        let todo = manager.todo_by_id(id);
+        assert_eq!(manager.undo(), Ok(Operation::EmptyTrash));
+        assert_eq!(manager.restore_todo(id), Ok(()));
+    }
 
-        assert_eq!(todo, Some(&manager.todos[2]))
+    #[test]
+    fn archive_done_todos_skips_unfinished_subtrees() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let done_child = manager.add_subtask(parent, "Ipsum").unwrap();
+        manager.add_subtask(parent, "Dolor").unwrap();
+
+        manager
+            .change_todo_status(parent, TodoStatus::Done)
+            .unwrap();
+        manager
+            .change_todo_status(done_child, TodoStatus::Done)
+            .unwrap();
+
+        assert_eq!(manager.archive_done_todos(), vec![done_child]);
+        assert_eq!(
+            manager.archive_todo(parent),
+            Err(TodoError::NotDone(parent))
+        );
+        assert_eq!(manager.all_todos().len(), 2);
     }
 
     #[test]
-    fn try_getting_non_existent_todo_by_id() {
-        let manager = TodoManager::default();
+    fn uncheck_nonexistent_checklist_item_fails() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.add_checklist_item(id, "Ipsum").unwrap();
 
-        let todo = manager.todo_by_id(42);
+        let result = manager.uncheck_checklist_item(id, 1);
 
-        assert!(todo.is_none())
+        assert_eq!(result, Err(TodoError::ChecklistItemNotFound(id, 1)));
     }
 
     #[test]
-    fn edit_existing_todo_content_succeeds() {
+    fn set_field_replaces_value_and_type() {
         let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
 
-        let new_id = manager.add_todo("This is a nice TODO.");
+        manager
+            .set_todo_field(id, "ipsum", FieldValue::Number(1.0))
+            .unwrap();
+        manager
+            .set_todo_field(id, "ipsum", FieldValue::Bool(true))
+            .unwrap();
 
-        let test_content = "This is even better!";
-        let result = manager.edit_todo_content(new_id, test_content);
+        assert!(manager
+            .todos_by_field("ipsum", &FieldValue::Number(1.0))
+            .is_empty());
+        assert_eq!(
+            manager
+                .todos_by_field("ipsum", &FieldValue::Bool(true))
+                .len(),
+            1
+        );
 
-        assert_eq!(result, Ok(()));
+        manager.unset_todo_field(id, "ipsum").unwrap();
+        assert!(manager.todos_with_field("ipsum").is_empty());
+    }
 
-        let updated_content = &manager.todo_by_id(new_id).unwrap().content;
-        assert_eq!(updated_content, test_content)
+    #[test]
+    fn snoozed_todo_is_visible_from_its_hidden_until_date() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.set_todo_hidden_until(id, Some(date(5))).unwrap();
+
+        assert!(manager.visible_todos(date(4)).is_empty());
+        assert_eq!(manager.visible_todos(date(5)).len(), 1);
+        assert!(manager.snoozed_todos(date(5)).is_empty());
     }
 
     #[test]
-    fn edit_nonexistent_todo_content_fails() {
+    fn assign_nonexistent_todo_fails() {
         let mut manager = TodoManager::default();
 
-        let result = manager.edit_todo_content(1, "Some content.");
-        assert_eq!(result, Err(TodoError::NotFound(1)))
+        let result = manager.assign_todo(42, "lorem");
+
+        assert_eq!(result, Err(TodoError::NotFound(42)));
     }
 
     #[test]
-    fn change_existing_todo_status_succeeds() {
+    fn add_same_reference_twice_keeps_one() {
         let mut manager = TodoManager::default();
-        let new_id = manager.add_todo("Good to do.");
+        let id = manager.add_todo("Lorem");
+        let other = manager.add_todo("Ipsum");
 
-        let new_state = TodoStatus::InProgress;
+        manager
+            .add_todo_reference(id, Reference::Todo(other))
+            .unwrap();
+        manager
+            .add_todo_reference(id, Reference::Todo(other))
+            .unwrap();
+        assert_eq!(manager.todos[0].references.len(), 1);
 
-        let result = manager.change_todo_status(new_id, new_state);
-        assert_eq!(result, Ok(()));
+        manager
+            .remove_todo_reference(id, &Reference::Todo(other))
+            .unwrap();
+        assert!(manager.todos[0].references.is_empty());
+    }
 
-        let updated_state = &manager.todo_by_id(new_id).unwrap().status;
-        assert_eq!(*updated_state, new_state);
+    #[test]
+    fn undo_delete_cascade_restores_subtree_and_dependencies() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        let child = manager.add_subtask(parent, "Ipsum").unwrap();
+        let other = manager.add_todo("Dolor");
+        manager.add_dependency(other, child).unwrap();
+
+        manager.delete_todo_cascade(parent).unwrap();
+        assert_eq!(manager.undo(), Ok(Operation::Delete(parent)));
+
+        let ids: Vec<usize> = manager.all_todos().iter().map(|todo| todo.id()).collect();
+        assert_eq!(ids, vec![parent, child, other]);
+        assert_eq!(manager.todos[2].dependencies(), &BTreeSet::from([child]));
+        assert!(manager.trashed_todos().is_empty());
     }
 
     #[test]
-    fn change_nonexistent_todo_status_fails() {
+    fn undo_trash_and_archive_operations() {
         let mut manager = TodoManager::default();
+        let deleted = manager.add_todo("Lorem");
+        let archived = manager.add_todo("Ipsum");
+        manager
+            .change_todo_status(archived, TodoStatus::Done)
+            .unwrap();
 
-        let result = manager.change_todo_status(42, TodoStatus::Done);
-        assert_eq!(result, Err(TodoError::NotFound(42)));
+        manager.delete_todo(deleted).unwrap();
+        manager.restore_todo(deleted).unwrap();
+        manager.delete_todo(deleted).unwrap();
+        manager.empty_trash();
+        manager.archive_done_todos();
+
+        assert_eq!(manager.undo(), Ok(Operation::ArchiveDone));
+        assert_eq!(manager.todo_by_id(archived).unwrap().content, "Ipsum");
+
+        assert_eq!(manager.undo(), Ok(Operation::EmptyTrash));
+        assert_eq!(manager.trashed_todos()[0].id(), deleted);
+
+        manager.undo().unwrap();
+        assert_eq!(manager.undo(), Ok(Operation::Restore(deleted)));
+        assert!(manager.todo_by_id(deleted).is_none());
+
+        manager.redo().unwrap();
+        let ids: Vec<usize> = manager.all_todos().iter().map(|todo| todo.id()).collect();
+        assert_eq!(ids, vec![deleted, archived]);
     }
 
     #[test]
-    fn delete_existing_todo_succeeds() {
+    fn undo_finishing_repeating_todo_removes_next_occurrence() {
         let mut manager = TodoManager::default();
-        let new_id = manager.add_todo("Lorem Ipsum");
+        let id = manager.add_todo("Lorem");
+        manager.todos[0].recurrence = Some(Recurrence::Daily);
 
-        let result = manager.delete_todo(new_id);
-        assert_eq!(result, Ok(()));
+        manager.change_todo_status(id, TodoStatus::Done).unwrap();
+        assert_eq!(manager.undo(), Ok(Operation::Status(id)));
+
+        assert_eq!(manager.todos.len(), 1);
+        assert_eq!(manager.todos[0].status, TodoStatus::Open);
+        assert_eq!(manager.todos[0].recurrence, Some(Recurrence::Daily));
+
+        manager.redo().unwrap();
+        assert_eq!(manager.todos.len(), 2);
+    }
+
+    #[test]
+    fn undo_reverts_only_values_changed_by_operation() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.edit_todo_content(id, "Ipsum").unwrap();
+        manager.add_todo_tag(id, "dolor").unwrap();
+        manager.add_comment(id, "Sit", None).unwrap();
+
+        assert_eq!(manager.undo(), Ok(Operation::Tag(id)));
+        assert_eq!(manager.undo(), Ok(Operation::Edit(id)));
+
+        assert_eq!(manager.todos[0].content, "Lorem");
+        assert!(!manager.todos[0].has_tag("dolor"));
+        assert_eq!(manager.todos[0].comments.len(), 1);
+
+        manager.redo().unwrap();
+        manager.redo().unwrap();
+        assert_eq!(manager.todos[0].content, "Ipsum");
+        assert!(manager.todos[0].has_tag("dolor"));
+    }
+
+    #[test]
+    fn undo_grouped_add_with_options_removes_todo() {
+        let mut manager = TodoManager::default();
+
+        let id = manager
+            .grouped(|manager| {
+                let id = manager.add_todo("Lorem");
+                manager.set_todo_due_date(id, Some(date(1)))?;
+                manager.set_todo_priority(id, Priority::High)?;
+                manager.add_todo_tag(id, "ipsum")?;
+                manager.add_todo_tag(id, "dolor").map(|_| id)
+            })
+            .unwrap();
+
+        assert_eq!(manager.undo(), Ok(Operation::Add(id)));
+        assert!(manager.all_todos().is_empty());
+
+        assert_eq!(manager.redo(), Ok(Operation::Add(id)));
+        assert_eq!(manager.todos[0].due, Some(date(1)));
+        assert_eq!(manager.todos[0].tags.len(), 2);
+    }
+
+    #[test]
+    fn undo_all_operations_restores_added_todo() {
+        let mut manager = TodoManager::default();
+        let other = manager.add_todo("Lorem");
+        let id = manager.add_todo("Ipsum");
+        let added = manager.todos[1].clone();
+
+        manager.set_todo_notes(id, "Dolor").unwrap();
+        manager.set_todo_priority(id, Priority::High).unwrap();
+        manager.set_todo_due_date(id, Some(date(2))).unwrap();
+        manager.set_todo_scheduled_date(id, Some(date(1))).unwrap();
+        manager.set_todo_hidden_until(id, Some(date(1))).unwrap();
+        manager
+            .set_todo_recurrence(id, Some(Recurrence::Weekly))
+            .unwrap();
+        manager.add_dependency(id, other).unwrap();
+        manager.add_todo_tag(id, "sit").unwrap();
+        manager.assign_todo(id, "amet").unwrap();
+        manager
+            .add_todo_reference(id, Reference::Todo(other))
+            .unwrap();
+        manager
+            .set_todo_field(id, "consectetur", FieldValue::Number(1.0))
+            .unwrap();
+        manager.add_checklist_item(id, "Adipiscing").unwrap();
+        manager.check_checklist_item(id, 0).unwrap();
+        manager
+            .change_todo_status(id, TodoStatus::InProgress)
+            .unwrap();
+        manager.move_subtree(id, Some(other)).unwrap();
+        let modified = manager.todos[1].clone();
+
+        assert_eq!(manager.journal().undo.len(), 17);
+
+        while manager.journal().next_undo() != Some(Operation::Add(id)) {
+            manager.undo().unwrap();
+        }
+        assert!(is_unchanged(&manager.todos[1], &added));
+
+        while manager.redo().is_ok() {}
+        assert!(is_unchanged(&manager.todos[1], &modified));
+    }
+
+    #[test]
+    fn undo_add_of_commented_todo_keeps_comments_on_redo() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.add_comment(id, "Ipsum", None).unwrap();
+        manager.start_timer(id).unwrap();
+        manager.stop_timer().unwrap();
 
+        assert_eq!(manager.undo(), Ok(Operation::Add(id)));
         assert!(manager.todos.is_empty());
+
+        assert_eq!(manager.redo(), Ok(Operation::Add(id)));
+        assert_eq!(manager.todos[0].comments[0].text, "Ipsum");
+        assert_eq!(manager.todos[0].time_entries().len(), 1);
     }
 
     #[test]
-    fn delete_nonexistent_todo_fails() {
+    fn commenting_and_tracking_time_keep_undone_changes() {
         let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.edit_todo_content(id, "Ipsum").unwrap();
 
-        let result = manager.delete_todo(42);
-        assert_eq!(result, Err(TodoError::NotFound(42)));
+        manager.undo().unwrap();
+        manager.add_comment(id, "Dolor", None).unwrap();
+        manager.start_timer(id).unwrap();
+
+        assert_eq!(manager.redo(), Ok(Operation::Edit(id)));
+        assert_eq!(manager.todos[0].content, "Ipsum");
+        assert_eq!(manager.todos[0].comments.len(), 1);
+    }
+
+    #[test]
+    fn recording_change_forgets_undone_changes() {
+        let mut manager = TodoManager::default();
+        let id = manager.add_todo("Lorem");
+        manager.edit_todo_content(id, "Ipsum").unwrap();
+
+        manager.undo().unwrap();
+        assert_eq!(manager.journal().next_redo(), Some(Operation::Edit(id)));
+
+        manager.edit_todo_content(id, "Dolor").unwrap();
+        assert_eq!(manager.redo(), Err(TodoError::NothingToRedo));
+        assert_eq!(manager.todos[0].content, "Dolor");
+    }
+
+    #[test]
+    fn failed_operation_is_not_recorded() {
+        let mut manager = TodoManager::default();
+        let parent = manager.add_todo("Lorem");
+        manager.add_subtask(parent, "Ipsum").unwrap();
+
+        assert!(manager.delete_todo(parent).is_err());
+        assert!(manager.edit_todo_content(42, "Dolor").is_err());
+
+        assert_eq!(manager.journal().next_undo(), Some(Operation::Add(2)));
+    }
+
+    #[test]
+    fn find_todo_matches_content_of_active_todos_only() {
+        let mut manager = TodoManager::default();
+        let deleted = manager.add_todo("Deploy to staging");
+        let kept = manager.add_todo("Deploy to production");
+        manager
+            .set_todo_notes(kept, "Ask about staging first")
+            .unwrap();
+        manager.delete_todo(deleted).unwrap();
+
+        assert_eq!(
+            manager.find_todo("staging"),
+            Err(TodoError::NoMatch(String::from("staging")))
+        );
+        assert_eq!(manager.find_todo("DEPLOY").unwrap().id(), kept);
+    }
+
+    #[test]
+    fn find_todo_prefers_closer_matches() {
+        let mut manager = TodoManager::default();
+        let rust = manager.add_todo("Learn Rust");
+        manager.add_todo("Remove rusty nails");
+        let typo = manager.add_todo("Fix the deplyo script");
+        manager.add_todo("Fix the deploy script");
+
+        assert_eq!(manager.find_todo("rust").unwrap().id(), rust);
+        assert_eq!(manager.find_todo("lern rust").unwrap().id(), rust);
+        assert_eq!(manager.find_todo("deplyo").unwrap().id(), typo);
+        assert!(matches!(
+            manager.find_todo("fix script"),
+            Err(TodoError::AmbiguousSelector(_, candidates)) if candidates.len() == 2
+        ));
+    }
+
+    #[test]
+    fn insert_empty_subtree_inserts_nothing() {
+        let mut manager = TodoManager::default();
+
+        assert_eq!(manager.insert_subtree(Vec::new()), None);
+        assert_eq!(manager.id_counter(), 0);
     }
 }
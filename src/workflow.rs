@@ -0,0 +1,286 @@
+//! User-defined statuses of `Todo`s and rules for changing them.
+//!
+//! This module contains the [`Workflow`] type holding statuses a `Todo` can
+//! have on top of the built-in [`Open`](TodoStatus::Open),
+//! [`InProgress`](TodoStatus::InProgress) and [`Done`](TodoStatus::Done), and
+//! marking which of them count as done. The [`TransitionPolicy`] type restricts
+//! which status changes are allowed.
+//!
+//! # Examples
+//!
+//! ```
+//! use doru::todo::TodoStatus;
+//! use doru::workflow::{CustomStatus, Workflow};
+//!
+//! let workflow = Workflow::new(vec![
+//!     CustomStatus::new("Review"),
+//!     CustomStatus::done("Won't do"),
+//! ]);
+//!
+//! let review = TodoStatus::Custom(String::from("Review"));
+//! let wont_do = TodoStatus::Custom(String::from("Won't do"));
+//!
+//! assert!(!workflow.is_done(&review));
+//! assert!(workflow.is_done(&wont_do));
+//! assert!(workflow.is_done(&TodoStatus::Done));
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::todo::TodoStatus;
+
+/// A user-defined status of a Todo item.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct CustomStatus {
+    /// Name of the status.
+    pub name: String,
+
+    /// Whether a Todo item with this status counts as done.
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl CustomStatus {
+    /// Creates a new `CustomStatus` with the given name, not counting as done.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            done: false,
+        }
+    }
+
+    /// Creates a new `CustomStatus` with the given name, counting as done.
+    pub fn done(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            done: true,
+        }
+    }
+}
+
+/// Set of user-defined statuses a Todo item can have, in addition to the
+/// built-in ones.
+///
+/// The default `Workflow` has no custom statuses.
+#[derive(PartialEq, Eq, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Workflow {
+    statuses: Vec<CustomStatus>,
+}
+
+impl Workflow {
+    /// Creates a new `Workflow` with the given custom statuses.
+    pub fn new(statuses: Vec<CustomStatus>) -> Self {
+        Self { statuses }
+    }
+
+    /// Returns the custom statuses of this `Workflow`.
+    pub fn custom_statuses(&self) -> &[CustomStatus] {
+        &self.statuses
+    }
+
+    /// Returns the status as it is known to this `Workflow`, or [`None`] if
+    /// it is a custom status this `Workflow` doesn't define.
+    ///
+    /// Custom status names are matched case-insensitively, so the returned
+    /// status is spelled as defined in the `Workflow`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use doru::todo::TodoStatus;
+    /// use doru::workflow::{CustomStatus, Workflow};
+    ///
+    /// let workflow = Workflow::new(vec![CustomStatus::new("Review")]);
+    ///
+    /// assert_eq!(
+    ///     workflow.resolve(&TodoStatus::Custom(String::from("review"))),
+    ///     Some(TodoStatus::Custom(String::from("Review")))
+    /// );
+    ///
+    /// assert_eq!(
+    ///     workflow.resolve(&TodoStatus::Custom(String::from("Blocked"))),
+    ///     None
+    /// );
+    /// ```
+    pub fn resolve(&self, status: &TodoStatus) -> Option<TodoStatus> {
+        match status {
+            TodoStatus::Custom(name) => self
+                .find(name)
+                .map(|custom| TodoStatus::Custom(custom.name.clone())),
+            builtin => Some(builtin.clone()),
+        }
+    }
+
+    /// Returns `true` if a Todo item with the given status counts as done.
+    ///
+    /// Only [`Done`](TodoStatus::Done) of the built-in statuses counts as done.
+    /// Custom statuses this `Workflow` doesn't define never count as done.
+    pub fn is_done(&self, status: &TodoStatus) -> bool {
+        match status {
+            TodoStatus::Done => true,
+            TodoStatus::Custom(name) => self.find(name).is_some_and(|custom| custom.done),
+            _ => false,
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&CustomStatus> {
+        self.statuses
+            .iter()
+            .find(|custom| custom.name.to_lowercase() == name.to_lowercase())
+    }
+}
+
+/// A rule allowing a status to be reached only from the listed statuses.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionRule {
+    /// The restricted target status.
+    pub to: TodoStatus,
+
+    /// Statuses the target status may be reached from.
+    pub from: Vec<TodoStatus>,
+}
+
+/// Policy restricting which status changes of a Todo item are allowed.
+///
+/// The default `TransitionPolicy` allows changing any status to any other.
+///
+/// # Examples
+///
+/// ```
+/// use doru::todo::TodoStatus;
+/// use doru::workflow::TransitionPolicy;
+///
+/// let policy = TransitionPolicy::default()
+///     .allow_only(TodoStatus::Done, vec![TodoStatus::InProgress])
+///     .require_reopen();
+///
+/// assert!(policy.allows(&TodoStatus::InProgress, &TodoStatus::Done));
+/// assert!(!policy.allows(&TodoStatus::Open, &TodoStatus::Done));
+/// assert!(policy.requires_reopen());
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransitionPolicy {
+    rules: Vec<TransitionRule>,
+    require_reopen: bool,
+}
+
+impl TransitionPolicy {
+    /// Allows the target status to be reached only from the listed statuses.
+    ///
+    /// If the target status is restricted more than once, a change has to
+    /// satisfy all the restrictions.
+    pub fn allow_only(mut self, to: TodoStatus, from: Vec<TodoStatus>) -> Self {
+        self.rules.push(TransitionRule { to, from });
+        self
+    }
+
+    /// Requires Todo items that are done to be explicitly reopened to get a
+    /// status that doesn't count as done.
+    pub fn require_reopen(mut self) -> Self {
+        self.require_reopen = true;
+        self
+    }
+
+    /// Returns `true` if the policy requires Todo items that are done to be
+    /// explicitly reopened.
+    pub fn requires_reopen(&self) -> bool {
+        self.require_reopen
+    }
+
+    /// Returns `true` if the rules of the policy allow changing status `from`
+    /// to status `to`. Keeping the same status is always allowed.
+    ///
+    /// Custom status names are compared case-insensitively.
+    pub fn allows(&self, from: &TodoStatus, to: &TodoStatus) -> bool {
+        if same_status(from, to) {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| same_status(&rule.to, to))
+            .all(|rule| rule.from.iter().any(|allowed| same_status(allowed, from)))
+    }
+}
+
+fn same_status(first: &TodoStatus, second: &TodoStatus) -> bool {
+    first.to_string().to_lowercase() == second.to_string().to_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_workflow_resolves_only_builtin_statuses() {
+        let workflow = Workflow::default();
+
+        assert_eq!(workflow.resolve(&TodoStatus::Open), Some(TodoStatus::Open));
+        assert_eq!(
+            workflow.resolve(&TodoStatus::Custom(String::from("Review"))),
+            None
+        );
+    }
+
+    #[test]
+    fn default_policy_allows_any_transition() {
+        let policy = TransitionPolicy::default();
+
+        assert!(policy.allows(&TodoStatus::Done, &TodoStatus::Open));
+        assert!(policy.allows(&TodoStatus::Open, &TodoStatus::Done));
+        assert!(!policy.requires_reopen());
+    }
+
+    #[test]
+    fn policy_combines_rules_for_same_status() {
+        let review = TodoStatus::Custom(String::from("Review"));
+
+        let policy = TransitionPolicy::default()
+            .allow_only(
+                TodoStatus::Done,
+                vec![TodoStatus::InProgress, review.clone()],
+            )
+            .allow_only(TodoStatus::Done, vec![review.clone()]);
+
+        assert!(policy.allows(
+            &TodoStatus::Custom(String::from("review")),
+            &TodoStatus::Done
+        ));
+        assert!(!policy.allows(&TodoStatus::InProgress, &TodoStatus::Done));
+        assert!(policy.allows(&TodoStatus::Done, &TodoStatus::Done));
+    }
+
+    #[test]
+    fn parse_policy_from_json() {
+        let json = r#"{
+            "rules": [{"to": "Done", "from": ["in-progress"]}],
+            "require_reopen": true
+        }"#;
+
+        let policy: TransitionPolicy = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            policy,
+            TransitionPolicy::default()
+                .allow_only(TodoStatus::Done, vec![TodoStatus::InProgress])
+                .require_reopen()
+        );
+    }
+
+    #[test]
+    fn parse_workflow_from_json() {
+        let json = r#"[{"name": "Review"}, {"name": "Won't do", "done": true}]"#;
+
+        let workflow: Workflow = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            workflow,
+            Workflow::new(vec![
+                CustomStatus::new("Review"),
+                CustomStatus::done("Won't do")
+            ])
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! A `Workspace`, holding several named lists of `Todo`s.
+//!
+//! This module contains the [`Workspace`] type, grouping [`TodoManager`]s
+//! under project names and providing methods for moving [`Todo`]s between
+//! them. Every `Workspace` has a [`DEFAULT_PROJECT`].
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> Result<(), doru::TodoError> {
+//! use doru::workspace::{Workspace, DEFAULT_PROJECT};
+//!
+//! let mut workspace = Workspace::default();
+//!
+//! // Add a Todo to a new project
+//! let id = workspace
+//!     .project_or_create("backend")
+//!     .add_todo("Fix the API");
+//!
+//! // Move the Todo to the default project
+//! let new_id = workspace.move_todo(id, "backend", DEFAULT_PROJECT)?;
+//!
+//! let default_project = workspace.project(DEFAULT_PROJECT).unwrap();
+//! assert_eq!(
+//!     default_project.todo_by_id(new_id).unwrap().content,
+//!     "Fix the API"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Todo`]: crate::todo::Todo
+
+use std::collections::BTreeMap;
+
+use crate::todo_manager::TodoManager;
+use crate::workflow::{TransitionPolicy, Workflow};
+use crate::TodoError;
+
+/// Name of the project every [`Workspace`] has.
+pub const DEFAULT_PROJECT: &str = "default";
+
+/// A `Workspace`, holding several named lists of Todo items, each managed by
+/// its own [`TodoManager`].
+///
+/// A [`Workflow`] and a [`TransitionPolicy`] set on the `Workspace` apply to
+/// all its projects.
+pub struct Workspace {
+    projects: BTreeMap<String, TodoManager>,
+    workflow: Workflow,
+    transition_policy: TransitionPolicy,
+}
+
+impl Default for Workspace {
+    /// Creates a `Workspace` with an empty [`DEFAULT_PROJECT`].
+    fn default() -> Self {
+        Self::new(BTreeMap::new())
+    }
+}
+
+impl Workspace {
+    /// Creates a new `Workspace` holding the provided projects, adding an
+    /// empty [`DEFAULT_PROJECT`] if it is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # use doru::todo_manager::TodoManager;
+    /// # use doru::workspace::{Workspace, DEFAULT_PROJECT};
+    /// let projects =
+    ///     BTreeMap::from([(String::from("backend"), TodoManager::default())]);
+    /// let workspace = Workspace::new(projects);
+    ///
+    /// assert_eq!(workspace.project_names(), vec!["backend", DEFAULT_PROJECT]);
+    /// ```
+    pub fn new(mut projects: BTreeMap<String, TodoManager>) -> Self {
+        projects.entry(String::from(DEFAULT_PROJECT)).or_default();
+
+        Self {
+            projects,
+            workflow: Workflow::default(),
+            transition_policy: TransitionPolicy::default(),
+        }
+    }
+
+    /// Makes all projects of the `Workspace` accept custom statuses defined by
+    /// the provided [`Workflow`], see [`TodoManager::with_workflow`].
+    pub fn with_workflow(mut self, workflow: Workflow) -> Self {
+        for manager in self.projects.values_mut() {
+            *manager = std::mem::take(manager).with_workflow(workflow.clone());
+        }
+
+        self.workflow = workflow;
+        self
+    }
+
+    /// Makes all projects of the `Workspace` allow only status changes
+    /// permitted by the provided [`TransitionPolicy`], see
+    /// [`TodoManager::with_transition_policy`].
+    pub fn with_transition_policy(mut self, policy: TransitionPolicy) -> Self {
+        for manager in self.projects.values_mut() {
+            *manager = std::mem::take(manager).with_transition_policy(policy.clone());
+        }
+
+        self.transition_policy = policy;
+        self
+    }
+
+    /// Returns names of all projects in the `Workspace`, in alphabetical order.
+    pub fn project_names(&self) -> Vec<&str> {
+        self.projects.keys().map(String::as_str).collect()
+    }
+
+    /// Returns a reference to the [`TodoManager`] of the project with the
+    /// provided name, if it exists, otherwise returns [`None`].
+    pub fn project(&self, name: &str) -> Option<&TodoManager> {
+        self.projects.get(name)
+    }
+
+    /// Returns a mutable reference to the [`TodoManager`] of the project with
+    /// the provided name, if it exists, otherwise returns [`None`].
+    pub fn project_mut(&mut self, name: &str) -> Option<&mut TodoManager> {
+        self.projects.get_mut(name)
+    }
+
+    /// Returns a mutable reference to the [`TodoManager`] of the project with
+    /// the provided name, creating an empty project if it doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::workspace::Workspace;
+    /// let mut workspace = Workspace::default();
+    /// workspace
+    ///     .project_or_create("backend")
+    ///     .add_todo("Fix the API");
+    ///
+    /// assert_eq!(workspace.project("backend").unwrap().all_todos().len(), 1);
+    /// ```
+    pub fn project_or_create(&mut self, name: &str) -> &mut TodoManager {
+        self.projects.entry(String::from(name)).or_insert_with(|| {
+            TodoManager::default()
+                .with_workflow(self.workflow.clone())
+                .with_transition_policy(self.transition_policy.clone())
+        })
+    }
+
+    /// Moves a Todo item with the provided id, together with all its subtasks,
+    /// from one project to another, creating the target project if it doesn't
+    /// exist. Then returns the new id of the moved Todo item.
+    ///
+    /// Ids are unique within a project only, so the moved Todo items get new
+    /// ids issued by the target project and anything referring to them by
+    /// their old ids has to be updated. Their old ids are not reused by the
+    /// source project.
+    ///
+    /// The moved Todo item becomes a top-level one in the target project.
+    /// Dependencies and references between the moved Todo items follow them
+    /// to their new ids, while the ones between the moved Todo items and the
+    /// ones left behind are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the source project doesn't exist, or if no Todo item
+    /// with provided id exists in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use doru::workspace::{Workspace, DEFAULT_PROJECT};
+    /// let mut workspace = Workspace::default();
+    /// let id = workspace
+    ///     .project_or_create(DEFAULT_PROJECT)
+    ///     .add_todo("Fix the API");
+    ///
+    /// workspace.move_todo(id, DEFAULT_PROJECT, "backend").unwrap();
+    ///
+    /// let default_project = workspace.project(DEFAULT_PROJECT).unwrap();
+    /// assert!(default_project.all_todos().is_empty());
+    ///
+    /// // Try to move a Todo from a non-existent project
+    /// let result = workspace.move_todo(id, "frontend", "backend");
+    /// assert!(result.is_err());
+    /// ```
+    pub fn move_todo(&mut self, id: usize, from: &str, to: &str) -> Result<usize, TodoError> {
+        let source = self
+            .projects
+            .get_mut(from)
+            .ok_or_else(|| TodoError::ProjectNotFound(String::from(from)))?;
+
+        if from == to {
+            return source
+                .todo_by_id(id)
+                .map(|todo| todo.id())
+                .ok_or(TodoError::NotFound(id));
+        }
+
+        let todos = source.take_subtree(id)?;
+
+        self.project_or_create(to)
+            .insert_subtree(todos)
+            .ok_or(TodoError::NotFound(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::journal::Operation;
+    use crate::reference::Reference;
+    use crate::todo::TodoStatus;
+    use crate::workflow::CustomStatus;
+
+    #[test]
+    fn default_workspace_has_default_project() {
+        let workspace = Workspace::default();
+
+        assert_eq!(workspace.project_names(), vec![DEFAULT_PROJECT]);
+    }
+
+    #[test]
+    fn created_projects_use_workspace_workflow() {
+        let workflow = Workflow::new(vec![CustomStatus::new("Review")]);
+        let mut workspace = Workspace::default().with_workflow(workflow.clone());
+
+        let manager = workspace.project_or_create("backend");
+
+        assert_eq!(manager.workflow(), &workflow);
+    }
+
+    #[test]
+    fn move_todo_moves_whole_subtree() {
+        let mut workspace = Workspace::default();
+
+        let source = workspace.project_or_create("lorem");
+        let other = source.add_todo("Ipsum");
+        let parent = source.add_subtask(other, "Dolor").unwrap();
+        let child = source.add_subtask(parent, "Sit").unwrap();
+        source.add_dependency(child, other).unwrap();
+
+        workspace.project_or_create("amet").add_todo("Consectetur");
+
+        let new_id = workspace.move_todo(parent, "lorem", "amet").unwrap();
+
+        let source = workspace.project("lorem").unwrap();
+        assert_eq!(source.all_todos().len(), 1);
+
+        let target = workspace.project("amet").unwrap();
+        assert_eq!(new_id, 2);
+
+        let moved = target.todo_by_id(new_id).unwrap();
+        assert_eq!(moved.content, "Dolor");
+        assert_eq!(moved.parent(), None);
+
+        let moved_child = &target.children(new_id)[0];
+        assert_eq!(moved_child.content, "Sit");
+        assert!(moved_child.dependencies().is_empty());
+    }
+
+    #[test]
+    fn move_todo_renumbers_references_and_drops_outside_ones() {
+        let mut workspace = Workspace::default();
+
+        let source = workspace.project_or_create("lorem");
+        let outside = source.add_todo("Ipsum");
+        let parent = source.add_todo("Dolor");
+        let child = source.add_subtask(parent, "Sit").unwrap();
+        source.add_dependency(child, parent).unwrap();
+        source
+            .add_todo_reference(child, Reference::Todo(parent))
+            .unwrap();
+        source
+            .add_todo_reference(child, Reference::Todo(outside))
+            .unwrap();
+        source
+            .add_todo_reference(outside, Reference::Todo(child))
+            .unwrap();
+
+        let target = workspace.project_or_create("amet");
+        target.add_todo("Consectetur");
+        target.add_todo("Adipiscing");
+
+        let new_parent = workspace.move_todo(parent, "lorem", "amet").unwrap();
+
+        let target = workspace.project("amet").unwrap();
+        let moved_child = &target.children(new_parent)[0];
+        assert_eq!(moved_child.references, vec![Reference::Todo(new_parent)]);
+        assert_eq!(
+            moved_child.dependencies(),
+            &std::collections::BTreeSet::from([new_parent])
+        );
+
+        let source = workspace.project("lorem").unwrap();
+        assert!(source.todo_by_id(outside).unwrap().references.is_empty());
+    }
+
+    #[test]
+    fn move_todo_issues_new_id_and_retires_old_one() {
+        let mut workspace = Workspace::default();
+        let id = workspace.project_or_create("lorem").add_todo("Ipsum");
+        workspace.project_or_create("amet").add_todo("Dolor");
+
+        let new_id = workspace.move_todo(id, "lorem", "amet").unwrap();
+        assert_ne!(new_id, id);
+
+        let next_id = workspace.project_or_create("lorem").add_todo("Sit");
+        assert_eq!(next_id, id + 1);
+    }
+
+    #[test]
+    fn undo_of_moved_todo_fails_without_duplicating_it() {
+        let mut workspace = Workspace::default();
+        let id = workspace.project_or_create("lorem").add_todo("Ipsum");
+        workspace.project_or_create("amet");
+
+        workspace.move_todo(id, "lorem", "amet").unwrap();
+
+        let lorem = workspace.project_mut("lorem").unwrap();
+        assert_eq!(
+            lorem.undo(),
+            Err(TodoError::UndoConflict(Operation::Add(id)))
+        );
+        assert!(lorem.all_todos().is_empty());
+
+        let amet = workspace.project_mut("amet").unwrap();
+        assert_eq!(amet.all_todos().len(), 1);
+        assert_eq!(amet.redo(), Err(TodoError::NothingToRedo));
+    }
+
+    #[test]
+    fn move_nonexistent_todo_fails() {
+        let mut workspace = Workspace::default();
+
+        let result = workspace.move_todo(42, DEFAULT_PROJECT, "lorem");
+        assert_eq!(result, Err(TodoError::NotFound(42)));
+        assert_eq!(workspace.project_names(), vec![DEFAULT_PROJECT]);
+    }
+
+    #[test]
+    fn move_todo_within_same_project_keeps_it() {
+        let mut workspace = Workspace::default();
+        let id = workspace
+            .project_or_create(DEFAULT_PROJECT)
+            .add_todo("Lorem");
+
+        let result = workspace.move_todo(id, DEFAULT_PROJECT, DEFAULT_PROJECT);
+        assert_eq!(result, Ok(id));
+
+        let manager = workspace.project(DEFAULT_PROJECT).unwrap();
+        assert_eq!(manager.todos_by_status(TodoStatus::Open).len(), 1);
+    }
+}